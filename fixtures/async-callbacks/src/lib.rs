@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+uniffi::setup_scaffolding!();
+uniffi_dart_runtime::setup!();
+
+/// Key-value storage that Rust awaits, implemented in Rust or Dart.
+#[uniffi::export(with_foreign)]
+#[async_trait::async_trait]
+pub trait Storage: Send + Sync {
+    async fn get(&self, key: String) -> Option<String>;
+    async fn put(&self, key: String, value: String);
+    async fn count(&self) -> u32;
+}
+
+#[uniffi::export(callback_interface)]
+#[async_trait::async_trait]
+pub trait Fetcher: Send + Sync {
+    async fn fetch(&self, url: String) -> String;
+}
+
+#[derive(Default)]
+struct MemoryStorage {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+#[async_trait::async_trait]
+impl Storage for MemoryStorage {
+    async fn get(&self, key: String) -> Option<String> {
+        self.entries.lock().unwrap().get(&key).cloned()
+    }
+
+    async fn put(&self, key: String, value: String) {
+        self.entries.lock().unwrap().insert(key, value);
+    }
+
+    async fn count(&self) -> u32 {
+        self.entries.lock().unwrap().len() as u32
+    }
+}
+
+#[uniffi::export]
+pub fn make_memory_storage() -> Arc<dyn Storage> {
+    Arc::new(MemoryStorage::default())
+}
+
+#[uniffi::export]
+pub async fn store_and_load(
+    storage: Arc<dyn Storage>,
+    key: String,
+    value: String,
+) -> Option<String> {
+    storage.put(key.clone(), value).await;
+    storage.get(key).await
+}
+
+#[uniffi::export]
+pub async fn count_entries(storage: Arc<dyn Storage>) -> u32 {
+    storage.count().await
+}
+
+#[uniffi::export]
+pub async fn fetch_all(fetcher: Box<dyn Fetcher>, urls: Vec<String>) -> Vec<String> {
+    let mut pages = Vec::with_capacity(urls.len());
+    for url in urls {
+        pages.push(fetcher.fetch(url).await);
+    }
+    pages
+}
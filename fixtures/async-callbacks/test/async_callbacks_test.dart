@@ -0,0 +1,65 @@
+import 'package:test/test.dart';
+import '../async_callbacks.dart';
+
+class MapStorage extends Storage {
+  final entries = <String, String>{};
+
+  @override
+  Future<String?> get_(String key) async {
+    await Future.delayed(const Duration(milliseconds: 10));
+    return entries[key];
+  }
+
+  @override
+  Future<void> put(String key, String value) async {
+    await Future.delayed(const Duration(milliseconds: 10));
+    entries[key] = value;
+  }
+
+  @override
+  Future<int> count() async => entries.length;
+}
+
+class EchoFetcher implements Fetcher {
+  @override
+  Future<String> fetch(String url) async {
+    if (url.isEmpty) {
+      throw ArgumentError('empty url');
+    }
+    await Future.delayed(const Duration(milliseconds: 5));
+    return 'page at $url';
+  }
+}
+
+void main() {
+  test('Rust awaits a Dart storage', () async {
+    final storage = MapStorage();
+    expect(await storeAndLoad(storage, 'answer', '42'), equals('42'));
+    expect(storage.entries, equals({'answer': '42'}));
+    expect(await countEntries(storage), equals(1));
+  });
+
+  test('Rust storage is awaitable from Dart', () async {
+    final storage = makeMemoryStorage();
+    await storage.put('a', '1');
+    expect(await storage.get_('a'), equals('1'));
+    expect(await storage.get_('b'), isNull);
+    expect(await storeAndLoad(storage, 'b', '2'), equals('2'));
+    expect(await countEntries(storage), equals(2));
+    storage.dispose();
+  });
+
+  test('async callback interface methods', () async {
+    expect(
+      await fetchAll(EchoFetcher(), ['a', 'b']),
+      equals(['page at a', 'page at b']),
+    );
+  });
+
+  test('exceptions in async callbacks reach the caller', () async {
+    await expectLater(
+      fetchAll(EchoFetcher(), ['a', '']),
+      throwsA(isA<RustPanicException>()),
+    );
+  });
+}
@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn async_callbacks() -> Result<()> {
+    uniffi_dart::testing::run_library_mode_test("async_callbacks", None)
+}
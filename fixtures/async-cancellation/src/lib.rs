@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+/// Counts futures dropped before they finished, so tests can observe cancellation.
+struct DropGuard {
+    finished: bool,
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if !self.finished {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+async fn sleep_guarded(ms: u32) {
+    let mut guard = DropGuard { finished: false };
+    tokio::time::sleep(Duration::from_millis(ms.into())).await;
+    guard.finished = true;
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+pub async fn sleep(ms: u32) -> u32 {
+    sleep_guarded(ms).await;
+    ms
+}
+
+#[uniffi::export]
+pub fn dropped_futures() -> u32 {
+    DROPPED.load(Ordering::SeqCst)
+}
+
+#[derive(uniffi::Object)]
+pub struct Worker;
+
+#[uniffi::export(async_runtime = "tokio")]
+impl Worker {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self)
+    }
+
+    pub async fn work(&self, ms: u32) -> String {
+        sleep_guarded(ms).await;
+        format!("worked {ms}ms")
+    }
+}
+
+uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
@@ -0,0 +1,63 @@
+import 'package:test/test.dart';
+import '../async_cancellation.dart';
+
+void main() {
+  test('completes without a token', () async {
+    expect(await sleep(10), equals(10));
+  });
+
+  test('cancelling a function drops the Rust future', () async {
+    final dropped = droppedFutures();
+    final token = UniffiCancellationToken();
+    final stopwatch = Stopwatch()..start();
+    final future = sleep(5000, cancellationToken: token);
+    await Future.delayed(const Duration(milliseconds: 50));
+    token.cancel();
+
+    await expectLater(future, throwsA(isA<UniffiCancelledException>()));
+    expect(stopwatch.elapsedMilliseconds, lessThan(1000));
+    expect(token.isCancelled, isTrue);
+    expect(droppedFutures(), equals(dropped + 1));
+  });
+
+  test('an already cancelled token never starts the call', () async {
+    final dropped = droppedFutures();
+    final token = UniffiCancellationToken()..cancel();
+    await expectLater(
+      sleep(10, cancellationToken: token),
+      throwsA(isA<UniffiCancelledException>()),
+    );
+    expect(droppedFutures(), equals(dropped));
+  });
+
+  test('cancelling a method', () async {
+    final worker = Worker();
+    final token = UniffiCancellationToken();
+    final future = worker.work(5000, cancellationToken: token);
+    token.cancel();
+    await expectLater(future, throwsA(isA<UniffiCancelledException>()));
+  });
+
+  test('cancelling after completion is a no-op', () async {
+    final worker = Worker();
+    final token = UniffiCancellationToken();
+    expect(
+      await worker.work(10, cancellationToken: token),
+      equals('worked 10ms'),
+    );
+    token.cancel();
+  });
+
+  test('one token cancels several calls', () async {
+    final worker = Worker();
+    final token = UniffiCancellationToken();
+    final futures = [
+      sleep(5000, cancellationToken: token),
+      worker.work(5000, cancellationToken: token),
+    ];
+    token.cancel();
+    for (final future in futures) {
+      await expectLater(future, throwsA(isA<UniffiCancelledException>()));
+    }
+  });
+}
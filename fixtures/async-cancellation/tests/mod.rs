@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn async_cancellation() -> Result<()> {
+    uniffi_dart::testing::run_test("async_cancellation", "src/api.udl", None)
+}
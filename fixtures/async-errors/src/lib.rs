@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+/// Flat error: only the variant crosses the FFI.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum FlatError {
+    #[error("Not found")]
+    NotFound,
+    #[error("Permission denied")]
+    PermissionDenied,
+}
+
+/// Rich error: variants carry fields.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum RichError {
+    #[error("Invalid input: {reason}")]
+    InvalidInput { reason: String },
+    #[error("Timed out after {after_ms}ms")]
+    Timeout { after_ms: u32 },
+}
+
+/// Interface error: an object thrown as an error.
+#[derive(Debug, thiserror::Error, uniffi::Object)]
+#[error("{message}")]
+#[uniffi::export(Debug, Display)]
+pub struct InterfaceError {
+    message: String,
+}
+
+#[uniffi::export]
+impl InterfaceError {
+    fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+#[uniffi::export]
+pub async fn flat_error_fn(do_fail: bool) -> Result<u32, FlatError> {
+    if do_fail {
+        Err(FlatError::PermissionDenied)
+    } else {
+        Ok(42)
+    }
+}
+
+#[uniffi::export]
+pub async fn rich_error_fn(do_fail: bool) -> Result<String, RichError> {
+    if do_fail {
+        Err(RichError::InvalidInput {
+            reason: "empty".to_string(),
+        })
+    } else {
+        Ok("ok".to_string())
+    }
+}
+
+#[uniffi::export]
+pub async fn interface_error_fn(do_fail: bool) -> Result<(), Arc<InterfaceError>> {
+    if do_fail {
+        Err(Arc::new(InterfaceError {
+            message: "interface failure".to_string(),
+        }))
+    } else {
+        Ok(())
+    }
+}
+
+#[uniffi::export]
+pub async fn panicking_fn() -> u32 {
+    panic!("async panic");
+}
+
+#[derive(uniffi::Object)]
+pub struct AsyncThrower;
+
+#[uniffi::export]
+impl AsyncThrower {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self)
+    }
+
+    pub async fn flat_error_method(&self, do_fail: bool) -> Result<u32, FlatError> {
+        flat_error_fn(do_fail).await
+    }
+
+    pub async fn rich_error_method(&self, after_ms: u32) -> Result<String, RichError> {
+        if after_ms > 0 {
+            Err(RichError::Timeout { after_ms })
+        } else {
+            Ok("ok".to_string())
+        }
+    }
+
+    pub async fn interface_error_method(&self, do_fail: bool) -> Result<(), Arc<InterfaceError>> {
+        interface_error_fn(do_fail).await
+    }
+
+    pub async fn panicking_method(&self) -> String {
+        panic!("async method panic");
+    }
+}
+
+uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
@@ -0,0 +1,170 @@
+import 'package:test/test.dart';
+import '../async_errors.dart';
+
+void main() {
+  group('async functions', () {
+    test('flat enum errors', () async {
+      expect(await flatErrorFn(false), equals(42));
+      await expectLater(
+        flatErrorFn(true),
+        throwsA(
+          isA<PermissionDeniedFlatException>().having(
+            (e) => e.message,
+            'message',
+            'Permission denied',
+          ),
+        ),
+      );
+    });
+
+    test('flat enum errors keep the Rust message', () async {
+      try {
+        await flatErrorFn(true);
+        fail('flatErrorFn should throw');
+      } on FlatException catch (e) {
+        final kind = switch (e) {
+          NotFoundFlatException() => 'not found',
+          PermissionDeniedFlatException() => 'permission denied',
+        };
+        expect(kind, equals('permission denied'));
+        expect(e.toString(), contains('Permission denied'));
+      }
+    });
+
+    test('rich enum errors', () async {
+      expect(await richErrorFn(false), equals('ok'));
+      await expectLater(
+        richErrorFn(true),
+        throwsA(
+          isA<InvalidInputRichException>().having(
+            (e) => e.reason,
+            'reason',
+            'empty',
+          ),
+        ),
+      );
+    });
+
+    test('interface errors', () async {
+      await interfaceErrorFn(false);
+      await expectLater(
+        interfaceErrorFn(true),
+        throwsA(
+          isA<InterfaceException>().having(
+            (e) => e.message(),
+            'message',
+            'interface failure',
+          ),
+        ),
+      );
+    });
+
+    test('panics', () async {
+      await expectLater(
+        panickingFn(),
+        throwsA(
+          isA<RustPanicException>()
+              .having((e) => e.message, 'message', 'async panic')
+              .having((e) => e.functionName, 'functionName', 'panicking_fn'),
+        ),
+      );
+    });
+
+    test('panic hook sees panics before they are thrown', () async {
+      final seen = <RustPanicException>[];
+      onRustPanic = seen.add;
+      try {
+        await expectLater(panickingFn(), throwsA(isA<RustPanicException>()));
+      } finally {
+        onRustPanic = null;
+      }
+      expect(seen.map((e) => e.functionName), equals(['panicking_fn']));
+    });
+  });
+
+  group('async methods', () {
+    final thrower = AsyncThrower();
+
+    test('flat enum errors', () async {
+      expect(await thrower.flatErrorMethod(false), equals(42));
+      await expectLater(
+        thrower.flatErrorMethod(true),
+        throwsA(
+          isA<PermissionDeniedFlatException>().having(
+            (e) => e.message,
+            'message',
+            'Permission denied',
+          ),
+        ),
+      );
+    });
+
+    test('rich enum errors', () async {
+      expect(await thrower.richErrorMethod(0), equals('ok'));
+      await expectLater(
+        thrower.richErrorMethod(250),
+        throwsA(
+          isA<TimeoutRichException>().having(
+            (e) => e.afterMs,
+            'afterMs',
+            250,
+          ),
+        ),
+      );
+    });
+
+    test('interface errors', () async {
+      await thrower.interfaceErrorMethod(false);
+      await expectLater(
+        thrower.interfaceErrorMethod(true),
+        throwsA(isA<InterfaceException>()),
+      );
+    });
+
+    test('panics', () async {
+      await expectLater(
+        thrower.panickingMethod(),
+        throwsA(
+          isA<RustPanicException>().having(
+            (e) => e.functionName,
+            'functionName',
+            'AsyncThrower::panicking_method',
+          ),
+        ),
+      );
+    });
+  });
+
+  group('UniffiException', () {
+    test('catches every generated error type', () async {
+      await expectLater(flatErrorFn(true), throwsA(isA<UniffiException>()));
+      await expectLater(richErrorFn(true), throwsA(isA<UniffiException>()));
+      await expectLater(interfaceErrorFn(true), throwsA(isA<UniffiException>()));
+    });
+
+    test('carries the Rust type, message and function', () async {
+      try {
+        await flatErrorFn(true);
+        fail('flatErrorFn should throw');
+      } on UniffiException catch (e) {
+        expect(e.rustTypeName, equals('FlatError'));
+        expect(e.rustMessage, equals('Permission denied'));
+        expect(e.rustFunctionName, equals('flat_error_fn'));
+      }
+
+      try {
+        await AsyncThrower().richErrorMethod(250);
+        fail('richErrorMethod should throw');
+      } on UniffiException catch (e) {
+        expect(e.rustTypeName, equals('RichError'));
+        expect(e.rustMessage, isNull);
+        expect(e.rustFunctionName, equals('AsyncThrower::rich_error_method'));
+      }
+    });
+
+    test('errors created in Dart have no function name or message', () {
+      expect(NotFoundFlatException().rustFunctionName, isNull);
+      expect(NotFoundFlatException().rustMessage, isNull);
+    });
+  });
+}
@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn async_errors() -> Result<()> {
+    uniffi_dart::testing::run_test("async_errors", "src/api.udl", None)
+}
@@ -0,0 +1,161 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+uniffi::setup_scaffolding!();
+uniffi_dart_runtime::setup!();
+
+/// Counts live heap allocations, so the tests can check that every buffer crossing the FFI is
+/// eventually freed.
+struct CountingAllocator;
+
+static LIVE_ALLOCATIONS: AtomicI64 = AtomicI64::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            LIVE_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            LIVE_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE_ALLOCATIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[uniffi::export]
+pub fn live_allocations() -> i64 {
+    LIVE_ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+#[derive(uniffi::Record)]
+pub struct Payload {
+    pub name: String,
+    pub values: Vec<u32>,
+    pub attributes: HashMap<String, String>,
+    pub data: Vec<u8>,
+    pub note: Option<String>,
+}
+
+#[derive(uniffi::Enum)]
+pub enum Shape {
+    Point,
+    Circle { radius: f64 },
+    Label(String),
+}
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum LifecycleError {
+    #[error("rejected {0}")]
+    Rejected(String),
+}
+
+#[uniffi::export(callback_interface)]
+pub trait Transformer: Send + Sync {
+    fn transform(&self, input: String) -> String;
+}
+
+#[uniffi::export]
+pub fn greeting() -> String {
+    "hello from Rust".to_string()
+}
+
+#[uniffi::export]
+pub fn echo_string(value: String) -> String {
+    value
+}
+
+#[uniffi::export]
+pub fn echo_bytes(value: Vec<u8>) -> Vec<u8> {
+    value
+}
+
+#[uniffi::export]
+pub fn echo_optional(value: Option<String>) -> Option<String> {
+    value
+}
+
+#[uniffi::export]
+pub fn echo_payload(value: Payload) -> Payload {
+    value
+}
+
+#[uniffi::export]
+pub fn echo_shape(value: Shape) -> Shape {
+    value
+}
+
+#[uniffi::export]
+pub fn echo_duration(value: Duration) -> Duration {
+    value
+}
+
+#[uniffi::export]
+pub fn reject(reason: String) -> Result<(), LifecycleError> {
+    Err(LifecycleError::Rejected(reason))
+}
+
+#[uniffi::export]
+pub fn transform_with(transformer: Box<dyn Transformer>, input: String) -> String {
+    transformer.transform(input)
+}
+
+#[derive(uniffi::Object)]
+pub struct Resource {
+    name: String,
+}
+
+#[uniffi::export]
+impl Resource {
+    #[uniffi::constructor]
+    pub fn new(name: String) -> Arc<Self> {
+        Arc::new(Self { name })
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn sibling(&self, name: String) -> Arc<Self> {
+        Self::new(name)
+    }
+}
+
+/// Keeps a Dart transformer alive on the Rust side until the holder is dropped.
+#[derive(uniffi::Object)]
+pub struct TransformerHolder {
+    transformer: Box<dyn Transformer>,
+}
+
+#[uniffi::export]
+impl TransformerHolder {
+    #[uniffi::constructor]
+    pub fn new(transformer: Box<dyn Transformer>) -> Arc<Self> {
+        Arc::new(Self { transformer })
+    }
+
+    pub fn transform(&self, input: String) -> String {
+        self.transformer.transform(input)
+    }
+}
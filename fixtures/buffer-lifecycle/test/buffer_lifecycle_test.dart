@@ -0,0 +1,137 @@
+import 'dart:io';
+import 'dart:typed_data';
+
+import 'package:test/test.dart';
+import '../buffer_lifecycle.dart';
+
+class Shouter extends Transformer {
+  @override
+  String transform(String input) => input.toUpperCase();
+}
+
+final shouter = Shouter();
+
+final calls = <void Function()>[
+  () => echoString('a string that needs a buffer'),
+  () => echoBytes(Uint8List.fromList([1, 2, 3, 4, 5])),
+  () => echoOptional('present'),
+  () => echoOptional(null),
+  () => echoPayload(Payload(
+        'payload',
+        [1, 2, 3],
+        {'key': 'value'},
+        Uint8List.fromList([9, 8, 7]),
+        'note',
+      )),
+  () => echoShape(CircleShape(2.5)),
+  () => echoShape(LabelShape('label')),
+  () => echoDuration(const Duration(seconds: 3)),
+  () {
+    try {
+      reject('always');
+    } on LifecycleException {
+      // expected, the error buffer still has to be freed
+    }
+  },
+  () => transformWith(shouter, 'callback'),
+];
+
+void makeCalls(int count) {
+  for (var i = 0; i < count; i++) {
+    calls[i % calls.length]();
+  }
+}
+
+void main() {
+  test('values survive the round trip', () {
+    expect(echoString('hello'), equals('hello'));
+    expect(echoBytes(Uint8List.fromList([1, 2, 3])), equals([1, 2, 3]));
+    expect(echoOptional(null), isNull);
+    expect(echoShape(LabelShape('x')), equals(LabelShape('x')));
+    expect(transformWith(shouter, 'hi'), equals('HI'));
+    expect(
+      () => reject('no'),
+      throwsA(isA<RejectedLifecycleException>()
+          .having((e) => e.message, 'message', 'rejected no')),
+    );
+  });
+
+  test('lifted bytes outlive the buffer they came from', () {
+    final first = echoBytes(Uint8List.fromList([1, 2, 3]));
+    echoBytes(Uint8List.fromList([4, 5, 6]));
+    expect(first, equals([1, 2, 3]));
+  });
+
+  test('native allocations stay flat across a million calls', () {
+    // Warm up lazily initialised state on both sides first
+    makeCalls(10000);
+    final allocationsBefore = liveAllocations();
+    final rssBefore = ProcessInfo.currentRss;
+
+    makeCalls(1000000);
+
+    // Every buffer Rust allocated for the calls above has been freed again
+    expect(liveAllocations(), equals(allocationsBefore));
+    // Dart side staging memory is not visible to the Rust allocator, a leak of even a few bytes
+    // per call would still show up as tens of megabytes here
+    expect(ProcessInfo.currentRss - rssBefore, lessThan(32 * 1024 * 1024));
+  });
+
+  group('debug stats', () {
+    test('count live objects until they are disposed', () {
+      final before = uniffiDebugStats().liveObjects['Resource'] ?? 0;
+      final resource = Resource('first');
+      final sibling = resource.sibling('second');
+      expect(uniffiDebugStats().liveObjects['Resource'], equals(before + 2));
+
+      resource.dispose();
+      sibling.dispose();
+      expect(uniffiDebugStats().liveObjects['Resource'] ?? 0, equals(before));
+    });
+
+    test('no RustBuffers are left outstanding', () {
+      makeCalls(1000);
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(0));
+    });
+
+    test('count buffers allocated but never handed to Rust', () {
+      final before = uniffiDebugStats().outstandingRustBuffers;
+      final leaked = toRustBuffer(Uint8List.fromList([1, 2, 3]));
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(before + 1));
+
+      leaked.free();
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(before));
+    });
+
+    test('count buffers lowered but never passed to Rust', () {
+      final before = uniffiDebugStats().outstandingRustBuffers;
+      final lowered = FfiConverterString.lower('never passed');
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(before + 1));
+
+      lowered.free();
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(before));
+    });
+
+    test('count buffers returned by Rust but never lifted', () {
+      final before = uniffiDebugStats().outstandingRustBuffers;
+      final leaked = rustCall(
+          (status) => uniffi_buffer_lifecycle_fn_func_greeting(status));
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(before + 1));
+
+      leaked.free();
+      expect(uniffiDebugStats().outstandingRustBuffers, equals(before));
+    });
+
+    test('count callback handles held by Rust', () {
+      transformWith(shouter, 'dropped after the call');
+      expect(uniffiDebugStats().handleMapEntries['Transformer'], isNull);
+
+      final holder = TransformerHolder(shouter);
+      expect(holder.transform('held'), equals('HELD'));
+      expect(uniffiDebugStats().handleMapEntries['Transformer'], equals(1));
+
+      holder.dispose();
+      expect(uniffiDebugStats().handleMapEntries['Transformer'], isNull);
+    });
+  });
+}
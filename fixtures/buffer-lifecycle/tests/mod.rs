@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn buffer_lifecycle() -> Result<()> {
+    uniffi_dart::testing::run_library_mode_test("buffer_lifecycle", Some("uniffi.toml"))
+}
@@ -1,5 +1,3 @@
-use uniffi;
-
 trait ForeignGetters {
     fn get_bool(&self, v: bool, argument_two: bool) -> Result<bool, SimpleError>;
     fn get_string(&self, v: String, arg2: bool) -> Result<String, SimpleError>;
@@ -103,6 +101,8 @@ impl Default for RustGetters {
 #[allow(clippy::wrong_self_convention)]
 trait StoredForeignStringifier: Send + Sync + std::fmt::Debug {
     fn from_simple_type(&self, value: i32) -> String;
+    // Only exercised by the bindings, to check types are collected from callback interfaces.
+    #[allow(dead_code)]
     fn from_complex_type(&self, values: Option<Vec<Option<f64>>>) -> String;
 }
 
@@ -123,3 +123,4 @@ impl RustStringifier {
 }
 
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
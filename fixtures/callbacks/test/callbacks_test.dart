@@ -9,11 +9,11 @@ class DartGetters extends ForeignGetters {
   String getString(String v, bool arg2) {
     if (v == 'BadArgument') {
       // Throw a UniFFI-generated exception type corresponding to BadArgument
-      throw SimpleException.badArgument;
+      throw BadArgumentSimpleException();
     }
     if (v == 'UnexpectedException') {
       // Throw a UniFFI-generated exception type corresponding to UnexpectedError
-      throw SimpleException.unexpectedError;
+      throw UnexpectedErrorSimpleException();
     }
     return arg2 ? v : '1234567890123';
   }
@@ -24,7 +24,7 @@ class DartGetters extends ForeignGetters {
       throw ReallyBadArgumentComplexException(20); // Example of a complex error
     }
     if (v == 'UnexpectedError') {
-      throw UnexpectedExceptionWithReasonComplexException("something failed");
+      throw UnexpectedErrorWithReasonComplexException("something failed");
     }
     return arg2 ? v?.toUpperCase() : v;
   }
@@ -35,14 +35,23 @@ class DartGetters extends ForeignGetters {
   @override
   void getNothing(String v) {
     if (v == 'BadArgument') {
-      throw SimpleException.badArgument;
+      throw BadArgumentSimpleException();
     }
     if (v == 'UnexpectedError') {
-      throw SimpleException.unexpectedError;
+      throw UnexpectedErrorSimpleException();
     }
   }
 }
 
+class ThrowingGetters extends DartGetters {
+  @override
+  String? getOption(String? v, bool arg2) =>
+      throw StateError('not a mapped error');
+
+  @override
+  void getNothing(String v) => throw StateError('not a mapped error');
+}
+
 class StoredDartStringifier extends StoredForeignStringifier {
   @override
   String fromSimpleType(int value) => 'kotlin: $value';
@@ -118,63 +127,120 @@ void main() {
     rustGetters.getNothing(callback, "1234567890123");
   });
 
-  // test('getString throws SimpleException.BadArgument', () {
-  //   final v = rustGetters.getString(callback, "BadArgument", true);
-  //   expect(v, throwsA(isA<Exception>()));
-  // });
-
-  // test('getString throws SimpleException.UnexpectedException', () {
-  //   expect(() => rustGetters.getString(callback, "UnexpectedError", false),
-  //       throwsA(isA<Exception>));
-  // });
-
-  // test('getOption throws ReallyBadArgumentComplexException', () {
-  //   // We expect ReallyBadArgumentComplexException with code=20
-  //   expect(
-  //       () => rustGetters.getOption(callback, "BadArgument", false),
-  //       throwsA(predicate(
-  //           (e) => e is ReallyBadArgumentComplexException && e.code == 20)));
-  // });
-
-  // test('getOption throws UnexpectedExceptionWithReasonComplexException', () {
-  //   // We expect UnexpectedExceptionWithReasonComplexException with reason matching "something failed"
-  //   expect(
-  //       () => rustGetters.getOption(callback, "UnexpectedError", false),
-  //       throwsA(predicate((e) =>
-  //           e is UnexpectedExceptionWithReasonComplexException &&
-  //           e.reason == Exception("something failed").toString())));
-  // });
-
-  // test('getNothing throws SimpleException.BadArgument', () {
-  //   rustGetters.getNothing(callback, "BadArgument");
-  //   // expect(() => rustGetters.getNothing(callback, "BadArgument"),
-  //   //     throwsA(isA<SimpleException>()));
-  // });
-
-  // test('getNothing throws SimpleException.UnexpectedException', () {
-  //   rustGetters.getNothing(callback, "UnexpectedError");
-  //   // expect(() => rustGetters.getNothing(callback, "UnexpectedError"),
-  //   //     throwsA(isA<SimpleException>()));
-  // });
-
-  // test('destroy RustGetters', () {
-  //   rustGetters.dispose();
-  //   // No assertions; just ensure no errors are thrown.
-  // });
-
-  // test('RustStringifier constructed with callback', () {
-  //   final dartStringifier = StoredDartStringifier();
-  //   final rustStringifier2 = RustStringifier(dartStringifier);
-  //   for (final v in [1, 2]) {
-  //     final expected = dartStringifier.fromSimpleType(v);
-  //     final observed = rustStringifier2.fromSimpleType(v);
-  //     expect(observed, equals(expected));
-  //   }
-  //   rustStringifier2.dispose();
-  // });
-
-  // // Clean up
-  // tearDownAll(() {
-  //   rustStringifier.dispose();
-  // });
+  test('getString propagates BadArgumentSimpleException', () {
+    expect(
+      () => rustGetters.getString(callback, "BadArgument", true),
+      throwsA(
+        isA<BadArgumentSimpleException>().having(
+          (e) => e.message,
+          'message',
+          'BadArgument',
+        ),
+      ),
+    );
+  });
+
+  test('errors record the Rust method that raised them', () {
+    expect(
+      () => rustGetters.getString(callback, "BadArgument", true),
+      throwsA(
+        isA<UniffiException>().having(
+          (e) => e.rustFunctionName,
+          'rustFunctionName',
+          'RustGetters::get_string',
+        ),
+      ),
+    );
+  });
+
+  test('getString propagates UnexpectedErrorSimpleException', () {
+    expect(
+      () => rustGetters.getString(callback, "UnexpectedException", false),
+      throwsA(
+        isA<UnexpectedErrorSimpleException>().having(
+          (e) => e.message,
+          'message',
+          'InternalTelephoneError',
+        ),
+      ),
+    );
+  });
+
+  test('getOption propagates ReallyBadArgumentComplexException', () {
+    expect(
+      () => rustGetters.getOption(callback, "BadArgument", false),
+      throwsA(
+        isA<ReallyBadArgumentComplexException>().having(
+          (e) => e.code,
+          'code',
+          20,
+        ),
+      ),
+    );
+  });
+
+  test('getOption propagates UnexpectedErrorWithReasonComplexException', () {
+    expect(
+      () => rustGetters.getOption(callback, "UnexpectedError", false),
+      throwsA(
+        isA<UnexpectedErrorWithReasonComplexException>().having(
+          (e) => e.reason,
+          'reason',
+          'something failed',
+        ),
+      ),
+    );
+  });
+
+  test('getNothing propagates BadArgumentSimpleException', () {
+    expect(
+      () => rustGetters.getNothing(callback, "BadArgument"),
+      throwsA(
+        isA<BadArgumentSimpleException>().having(
+          (e) => e.message,
+          'message',
+          'BadArgument',
+        ),
+      ),
+    );
+  });
+
+  test('unmapped exceptions become the unexpected error variant', () {
+    expect(
+      () => rustGetters.getNothing(ThrowingGetters(), "anything"),
+      throwsA(
+        isA<UnexpectedErrorSimpleException>().having(
+          (e) => e.message,
+          'message',
+          'InternalTelephoneError',
+        ),
+      ),
+    );
+    expect(
+      () => rustGetters.getOption(ThrowingGetters(), "anything", false),
+      throwsA(
+        isA<UnexpectedErrorWithReasonComplexException>().having(
+          (e) => e.reason,
+          'reason',
+          contains('not a mapped error'),
+        ),
+      ),
+    );
+  });
+
+  test('RustStringifier constructed with callback', () {
+    final dartStringifier = StoredDartStringifier();
+    final rustStringifier2 = RustStringifier(dartStringifier);
+    for (final v in [1, 2]) {
+      final expected = dartStringifier.fromSimpleType(v);
+      final observed = rustStringifier2.fromSimpleType(v);
+      expect(observed, equals(expected));
+    }
+    rustStringifier2.dispose();
+  });
+
+  tearDownAll(() {
+    rustStringifier.dispose();
+    rustGetters.dispose();
+  });
 }
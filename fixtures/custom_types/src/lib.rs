@@ -1,104 +1,75 @@
-// use uniffi;
-// use url::Url;
-
-// pub struct Handle(pub i64);
-
-// pub struct TimeIntervalMs(pub i64);
-
-// pub struct TimeIntervalSecDbl(pub f64);
-
-// pub struct TimeIntervalSecFlt(pub f32);
-
-// impl UniffiCustomTypeConverter for Handle {
-//     // The `Builtin` type will be used to marshall values across the FFI
-//     type Builtin = i64;
-
-//     // Convert Builtin to our custom type
-//     fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
-//         Ok(Handle(val))
-//     }
-
-//     // Convert our custom type to Builtin
-//     fn from_custom(obj: Self) -> Self::Builtin {
-//         obj.0
-//     }
-// }
-
-// impl UniffiCustomTypeConverter for Url {
-//     type Builtin = String;
-
-//     fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
-//         Ok(Url::parse(&val)?)
-//     }
-
-//     fn from_custom(obj: Self) -> Self::Builtin {
-//         obj.into()
-//     }
-// }
-
-// impl UniffiCustomTypeConverter for TimeIntervalMs {
-//     // The `Builtin` type will be used to marshall values across the FFI
-//     type Builtin = i64;
-
-//     // Convert Builtin to our custom type
-//     fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
-//         Ok(TimeIntervalMs(val))
-//     }
-
-//     // Convert our custom type to Builtin
-//     fn from_custom(obj: Self) -> Self::Builtin {
-//         obj.0
-//     }
-// }
-
-// impl UniffiCustomTypeConverter for TimeIntervalSecDbl {
-//     // The `Builtin` type will be used to marshall values across the FFI
-//     type Builtin = f64;
-
-//     // Convert Builtin to our custom type
-//     fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
-//         Ok(TimeIntervalSecDbl(val))
-//     }
-
-//     // Convert our custom type to Builtin
-//     fn from_custom(obj: Self) -> Self::Builtin {
-//         obj.0
-//     }
-// }
-
-// impl UniffiCustomTypeConverter for TimeIntervalSecFlt {
-//     // The `Builtin` type will be used to marshall values across the FFI
-//     type Builtin = f32;
-
-//     // Convert Builtin to our custom type
-//     fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
-//         Ok(TimeIntervalSecFlt(val))
-//     }
-
-//     // Convert our custom type to Builtin
-//     fn from_custom(obj: Self) -> Self::Builtin {
-//         obj.0
-//     }
-// }
-
-// #[derive(uniffi::Record)]
-// pub struct CustomTypesDemo {
-//     url: Url,
-//     handle: Handle,
-//     time_interval_ms: TimeIntervalMs,
-//     time_interval_sec_dbl: TimeIntervalSecDbl,
-//     time_interval_sec_flt: TimeIntervalSecFlt,
-// }
-
-// #[uniffi::export]
-// pub fn get_custom_types_demo(v: Option<CustomTypesDemo>) -> CustomTypesDemo {
-//     v.unwrap_or_else(|| CustomTypesDemo {
-//         url: Url::parse("http://example.com/").unwrap(),
-//         handle: Handle(123),
-//         time_interval_ms: TimeIntervalMs(456000),
-//         time_interval_sec_dbl: TimeIntervalSecDbl(456.0),
-//         time_interval_sec_flt: TimeIntervalSecFlt(777.0),
-//     })
-// }
-
-// uniffi::include_scaffolding!("api");
+use std::collections::HashMap;
+
+use url::Url;
+
+pub struct Handle(pub i64);
+
+pub struct TimeIntervalMs(pub i64);
+
+pub struct TimeIntervalSecDbl(pub f64);
+
+pub struct TimeIntervalSecFlt(pub f32);
+
+pub struct Tags(pub Vec<String>);
+
+uniffi::custom_type!(Url, String, {
+    remote,
+    try_lift: |val| Ok(Url::parse(&val)?),
+    lower: |obj| obj.into(),
+});
+
+uniffi::custom_newtype!(Handle, i64);
+uniffi::custom_newtype!(TimeIntervalMs, i64);
+uniffi::custom_newtype!(TimeIntervalSecDbl, f64);
+uniffi::custom_newtype!(TimeIntervalSecFlt, f32);
+uniffi::custom_newtype!(Tags, Vec<String>);
+
+pub struct CustomTypesDemo {
+    url: Url,
+    handle: Handle,
+    time_interval_ms: TimeIntervalMs,
+    time_interval_sec_dbl: TimeIntervalSecDbl,
+    time_interval_sec_flt: TimeIntervalSecFlt,
+    tags: Tags,
+}
+
+pub trait UrlRewriter: Send + Sync {
+    fn rewrite(&self, url: Url) -> Url;
+}
+
+fn get_custom_types_demo(v: Option<CustomTypesDemo>) -> CustomTypesDemo {
+    v.unwrap_or_else(|| CustomTypesDemo {
+        url: Url::parse("http://example.com/").unwrap(),
+        handle: Handle(123),
+        time_interval_ms: TimeIntervalMs(456000),
+        time_interval_sec_dbl: TimeIntervalSecDbl(456.0),
+        time_interval_sec_flt: TimeIntervalSecFlt(777.0),
+        tags: Tags(vec!["rust".to_string(), "dart".to_string()]),
+    })
+}
+
+fn get_url(url: Url) -> Url {
+    url
+}
+
+fn get_optional_url(url: Option<Url>) -> Option<Url> {
+    url
+}
+
+fn get_urls(urls: Vec<Url>) -> Vec<Url> {
+    urls
+}
+
+fn get_intervals(intervals: HashMap<String, TimeIntervalMs>) -> HashMap<String, TimeIntervalMs> {
+    intervals
+}
+
+fn get_handle(handle: Handle) -> Handle {
+    handle
+}
+
+fn rewrite_url(rewriter: Box<dyn UrlRewriter>, url: Url) -> Url {
+    rewriter.rewrite(url)
+}
+
+uniffi::include_scaffolding!("api");
@@ -1,17 +1,67 @@
+import 'dart:collection';
+
 import 'package:test/test.dart';
 import '../custom_types.dart';
 
+class HttpsRewriter implements UrlRewriter {
+  @override
+  Uri rewrite(Uri url) => url.replace(scheme: 'https');
+}
+
 void main() {
-  final api = Api.load();
-  
-  final demo = api.getCustomTypesDemo(null);
-
-  assert(demo.url.toString() == 'http://example.com/');
-  
-  assert(demo.handle == 123);
-
-  demo.url = Uri.parse('http://new.example.com/');
-  demo.handle = 456;
-  
-  assert(demo == api.getCustomTypesDemo(demo));
+  test('configured custom types lift to Dart types', () {
+    final demo = getCustomTypesDemo(null);
+
+    expect(demo.url, isA<Uri>());
+    expect(demo.url.toString(), equals('http://example.com/'));
+    expect(demo.timeIntervalMs, equals(Duration(seconds: 456)));
+    expect(demo.tags, isA<UnmodifiableListView<String>>());
+    expect(demo.tags, equals(['rust', 'dart']));
+  });
+
+  test('unconfigured custom types keep their builtin type', () {
+    final demo = getCustomTypesDemo(null);
+
+    expect(demo.handle, equals(123));
+    expect(demo.timeIntervalSecDbl, equals(456.0));
+    expect(demo.timeIntervalSecFlt, equals(777.0));
+    expect(getHandle(456), equals(456));
+  });
+
+  test('records round trip', () {
+    final demo = CustomTypesDemo(
+      Uri.parse('http://new.example.com/'),
+      456,
+      Duration(milliseconds: 1500),
+      1.5,
+      2.5,
+      UnmodifiableListView(['a', 'b']),
+    );
+    final returned = getCustomTypesDemo(demo);
+
+    expect(returned.url, equals(Uri.parse('http://new.example.com/')));
+    expect(returned.handle, equals(456));
+    expect(returned.timeIntervalMs, equals(Duration(milliseconds: 1500)));
+    expect(returned.tags, equals(['a', 'b']));
+  });
+
+  test('arguments, optionals, sequences and maps', () {
+    final url = Uri.parse('https://example.org/path?q=1');
+
+    expect(getUrl(url), equals(url));
+    expect(getOptionalUrl(url), equals(url));
+    expect(getOptionalUrl(null), isNull);
+    expect(getUrls([url, url]), equals([url, url]));
+    expect(
+      getIntervals({'short': Duration(milliseconds: 10)}),
+      equals({'short': Duration(milliseconds: 10)}),
+    );
+  });
+
+  test('callback arguments and returns', () {
+    expect(
+      rewriteUrl(HttpsRewriter(), Uri.parse('http://example.com/')),
+      equals(Uri.parse('https://example.com/')),
+    );
+  });
 }
@@ -64,3 +64,4 @@ pub trait CallbackTest: Send + Sync {
 }
 
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
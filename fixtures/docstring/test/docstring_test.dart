@@ -1,77 +1,25 @@
 import 'package:test/test.dart';
-import '../docstring.dart';
+import '../docstring.dart' as api;
 
+// Docstrings only show up as `///` comments in the generated library. These tests make sure
+// the documented API still compiles and behaves, comments included.
 void main() {
-  group('Docstring', () {
-    test('function with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Functions should have proper documentation comments in generated Dart code
-
-      // Test the basic function
-      test();
-
-      // Test the multiline function
-      testMultiline();
-
-      // Test function without docstring
-      testWithoutDocstring();
-    });
-
-    test('enum with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Enums should have proper documentation comments in generated Dart code
-
-      final enumValue = EnumTest.one;
-      expect(enumValue, equals(EnumTest.one));
-
-      final enumValue2 = EnumTest.two;
-      expect(enumValue2, equals(EnumTest.two));
-    });
-
-    test('associated enum with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Associated enums should have proper documentation comments
-
-      final associatedEnum = AssociatedEnumTest.test(code: 42);
-      expect(associatedEnum, isA<AssociatedEnumTest>());
-
-      final associatedEnum2 = AssociatedEnumTest.test2(code: 43);
-      expect(associatedEnum2, isA<AssociatedEnumTest>());
-    });
-
-    test('error with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Errors should have proper documentation comments
-
-      expect(() => test(), throwsA(isA<ErrorTest>()));
-    });
-
-    test('object with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Objects should have proper documentation comments
-
-      final obj = ObjectTest();
-      obj.test();
-
-      final objAlt = ObjectTest.newAlternate();
-      objAlt.test();
-    });
-
-    test('record with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Records should have proper documentation comments
-
-      final record = RecordTest(test: 42);
-      expect(record.test, equals(42));
-    });
+  test('documented functions', () {
+    api.test();
+    api.testMultiline();
+    api.testWithoutDocstring();
+  });
 
-    test('callback with docstring', () {
-      // This test will fail until docstring support is implemented
-      // Expected: Callbacks should have proper documentation comments
+  test('documented enums and errors', () {
+    expect(api.EnumTest.values, [api.EnumTest.one, api.EnumTest.two]);
+    expect(api.ErrorTest.values, [api.ErrorTest.one, api.ErrorTest.two]);
+    expect(api.TestAssociatedEnumTest(42).code, 42);
+    expect(api.Test2AssociatedErrorTest(43).code, 43);
+  });
 
-      // This will require callback interface implementation
-      // final callback = CallbackTestImpl();
-      // callback.test();
-    });
+  test('documented objects and records', () {
+    api.ObjectTest().test();
+    api.ObjectTest.newAlternate().test();
+    expect(api.RecordTest(42).test, 42);
   });
 }
@@ -1,7 +1,88 @@
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test_docstring() {
-        uniffi_dart::testing::run_test("docstring", "src/api.udl", None).unwrap();
-    }
+use anyhow::Result;
+
+#[test]
+fn docstring() -> Result<()> {
+    uniffi_dart::testing::run_test("docstring", "src/api.udl", None)
+}
+
+// Rendered by the build script
+const BINDINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/docstring.dart"));
+
+/// Asserts that the bindings render `docs` as a dartdoc comment right before `item`.
+fn assert_documented(docs: &[&str], item: &str) {
+    let comment: String = docs
+        .iter()
+        .map(|line| format!("{}\n", format!("/// {line}").trim_end()))
+        .collect();
+    let documented = BINDINGS.match_indices(&comment).any(|(start, _)| {
+        BINDINGS[start + comment.len()..]
+            .trim_start()
+            .starts_with(item)
+    });
+    assert!(documented, "`{item}` is not documented with {comment:?}");
+}
+
+#[test]
+fn docstrings_are_rendered_as_dartdoc() {
+    assert_documented(&["<docstring-function>"], "void test()");
+    assert_documented(
+        &[
+            "<docstring-multiline-function>",
+            "<second-line>",
+            "",
+            "Mentions [RecordTest] and a */ sequence, neither of which may break the comment.",
+        ],
+        "void testMultiline()",
+    );
+
+    let object = [
+        "<docstring-object>",
+        "Built from a [RecordTest]; {\\@macro x} is text, not a dartdoc directive.",
+    ];
+    assert_documented(&object, "abstract class ObjectTestInterface ");
+    assert_documented(&object, "class ObjectTest ");
+    assert_documented(&["<docstring-primary-constructor>"], "ObjectTest()");
+    assert_documented(
+        &["<docstring-alternate-constructor>"],
+        "ObjectTest.newAlternate()",
+    );
+    assert_documented(&["<docstring-method>"], "void test();");
+    assert_documented(&["<docstring-method>"], "void test() {");
+
+    assert_documented(&["<docstring-record>"], "class RecordTest ");
+    assert_documented(&["<docstring-record-field>"], "final int test;");
+
+    assert_documented(&["<docstring-enum>"], "enum EnumTest ");
+    assert_documented(&["<docstring-enum-variant>"], "one(");
+    assert_documented(&["<docstring-enum-variant-2>"], "two(");
+    assert_documented(
+        &["<docstring-associated-enum>"],
+        "sealed class AssociatedEnumTest ",
+    );
+    assert_documented(
+        &["<docstring-associated-enum-variant>"],
+        "final class TestAssociatedEnumTest ",
+    );
+    assert_documented(
+        &["<docstring-associated-enum-variant-2>"],
+        "final class Test2AssociatedEnumTest ",
+    );
+
+    assert_documented(&["<docstring-error>"], "sealed class ErrorTest ");
+    assert_documented(&["<docstring-error-variant>"], "final class OneErrorTest ");
+    assert_documented(
+        &["<docstring-error-variant-2>"],
+        "final class TwoErrorTest ",
+    );
+    assert_documented(
+        &["<docstring-associated-error>"],
+        "sealed class AssociatedErrorTest ",
+    );
+    assert_documented(
+        &["<docstring-associated-error-variant>"],
+        "final class TestAssociatedErrorTest ",
+    );
+
+    assert_documented(&["<docstring-callback>"], "abstract class CallbackTest ");
+    assert_documented(&["<docstring-callback-method>"], "void test();");
 }
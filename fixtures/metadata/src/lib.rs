@@ -53,3 +53,4 @@ pub fn get_metadata_struct() -> MetadataStruct {
 }
 
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
@@ -1,5 +1,6 @@
 // Pure proc-macro example - no UDL file needed!
 uniffi::setup_scaffolding!();
+uniffi_dart_runtime::setup!();
 
 #[derive(uniffi::Record)]
 pub struct Person {
@@ -33,6 +34,69 @@ pub fn status_to_string(user_status: UserStatus) -> String {
     }
 }
 
+#[derive(uniffi::Enum)]
+pub enum UserEvent {
+    LoggedOut,
+    Renamed(String),
+    Moved { from: Vec<u32>, to: Vec<u32> },
+}
+
+#[uniffi::export]
+pub fn echo_event(event: UserEvent) -> UserEvent {
+    event
+}
+
+#[uniffi::export]
+pub fn describe_event(event: UserEvent) -> String {
+    match event {
+        UserEvent::LoggedOut => "Logged out".to_string(),
+        UserEvent::Renamed(name) => format!("Renamed to {name}"),
+        UserEvent::Moved { from, to } => format!("Moved from {from:?} to {to:?}"),
+    }
+}
+
+#[derive(uniffi::Enum)]
+#[non_exhaustive]
+pub enum Flavor {
+    Vanilla,
+    Chocolate,
+}
+
+#[derive(uniffi::Enum)]
+#[non_exhaustive]
+pub enum Topping {
+    Sprinkles,
+    Sauce(String),
+}
+
+#[uniffi::export]
+pub fn echo_flavor(flavor: Flavor) -> Flavor {
+    flavor
+}
+
+#[uniffi::export]
+pub fn echo_topping(topping: Topping) -> Topping {
+    topping
+}
+
+#[uniffi::export]
+pub fn echo_toppings(toppings: Vec<Topping>) -> Vec<Topping> {
+    toppings
+}
+
+#[derive(uniffi::Enum)]
+#[repr(u8)]
+pub enum StatusCode {
+    Ok = 10,
+    NotFound = 20,
+    Teapot = 30,
+}
+
+#[uniffi::export]
+pub fn status_code_value(code: StatusCode) -> u8 {
+    code as u8
+}
+
 #[uniffi::export(default(iterations = 10000, length = 32))]
 pub fn hash_data(data: Option<Vec<u8>>, iterations: u32, length: u32) -> Vec<u8> {
     // Simple mock implementation for testing
@@ -63,11 +127,22 @@ impl Counter {
         }
     }
 
+    #[uniffi::constructor(default(initial = 100))]
+    fn starting_at(initial: i32) -> Self {
+        Counter::new(initial)
+    }
+
     fn increment(&self) {
         let mut value = self.value.lock().unwrap();
         *value += 1;
     }
 
+    #[uniffi::method(default(step = 10))]
+    fn increment_by(&self, step: i32) {
+        let mut value = self.value.lock().unwrap();
+        *value += step;
+    }
+
     fn get_value(&self) -> i32 {
         *self.value.lock().unwrap()
     }
@@ -25,6 +25,96 @@ void main() {
     });
   });
 
+  group('Rich enum tests', () {
+    String describe(UserEvent event) => switch (event) {
+          LoggedOutUserEvent() => 'logged out',
+          RenamedUserEvent(v0: final name) => 'renamed to $name',
+          MovedUserEvent(:final to) => 'moved to $to',
+        };
+
+    test('round trip', () {
+      expect(echoEvent(LoggedOutUserEvent()), isA<LoggedOutUserEvent>());
+      expect(describeEvent(RenamedUserEvent('Carol')), equals('Renamed to Carol'));
+      expect(
+        describeEvent(MovedUserEvent(from: [1, 2], to: [3])),
+        equals('Moved from [1, 2] to [3]'),
+      );
+    });
+
+    test('exhaustive switch', () {
+      expect(describe(echoEvent(LoggedOutUserEvent())), equals('logged out'));
+      expect(describe(echoEvent(RenamedUserEvent('Dan'))), equals('renamed to Dan'));
+      expect(
+        describe(echoEvent(MovedUserEvent(from: [1], to: [4, 5]))),
+        equals('moved to [4, 5]'),
+      );
+    });
+
+    test('value equality', () {
+      final moved = MovedUserEvent(from: [1, 2], to: [3]);
+      expect(echoEvent(moved), equals(moved));
+      expect(echoEvent(moved).hashCode, equals(moved.hashCode));
+      expect(LoggedOutUserEvent(), equals(LoggedOutUserEvent()));
+      expect(RenamedUserEvent('Eve'), equals(RenamedUserEvent('Eve')));
+      expect(RenamedUserEvent('Eve'), isNot(equals(RenamedUserEvent('Bob'))));
+      expect(moved, isNot(equals(MovedUserEvent(from: [1, 2], to: [4]))));
+    });
+  });
+
+  group('Non-exhaustive enum tests', () {
+    // Discriminant 99 stands in for a variant added by a newer native library
+    final unknownDiscriminant = Uint8List.fromList([0, 0, 0, 99]);
+
+    test('known variants round trip', () {
+      expect(echoFlavor(Flavor.chocolate), equals(Flavor.chocolate));
+      expect(echoTopping(SauceTopping('fudge')), equals(SauceTopping('fudge')));
+    });
+
+    test('unknown flat variant falls back to unknown', () {
+      expect(FfiConverterFlavor.read(unknownDiscriminant).value, equals(Flavor.unknown));
+    });
+
+    test('unknown rich variant keeps its discriminant', () {
+      final topping = FfiConverterTopping.lift(toRustBuffer(unknownDiscriminant));
+      expect(topping, isA<UnknownTopping>());
+      expect((topping as UnknownTopping).discriminant, equals(99));
+    });
+
+    test('unknown rich variant inside another value cannot be skipped', () {
+      // A sequence of two toppings, the first one unknown and followed by `Sprinkles`
+      final sequence = Uint8List.fromList([0, 0, 0, 2, 0, 0, 0, 99, 0, 0, 0, 1]);
+      expect(() => FfiConverterSequenceTopping.lift(toRustBuffer(sequence)),
+          throwsA(isA<UniffiInternalError>()));
+      expect(echoToppings([SprinklesTopping(), SauceTopping('fudge')]),
+          equals([SprinklesTopping(), SauceTopping('fudge')]));
+    });
+
+    test('unknown variants cannot be lowered', () {
+      expect(() => echoFlavor(Flavor.unknown), throwsA(isA<UniffiInternalError>()));
+      expect(() => echoTopping(UnknownTopping(99)), throwsA(isA<UniffiInternalError>()));
+    });
+  });
+
+  group('Enum discriminant tests', () {
+    test('values match the Rust discriminants', () {
+      expect(StatusCode.ok.value, equals(10));
+      expect(StatusCode.notFound.value, equals(statusCodeValue(StatusCode.notFound)));
+      expect(StatusCode.teapot.value, equals(statusCodeValue(StatusCode.teapot)));
+    });
+
+    test('implicit discriminants count from zero', () {
+      expect(UserStatus.values.map((s) => s.value), equals([0, 1, 2]));
+    });
+
+    test('fromValue', () {
+      expect(StatusCode.fromValue(20), equals(StatusCode.notFound));
+      expect(() => StatusCode.fromValue(11), throwsArgumentError);
+      expect(Flavor.fromValue(1), equals(Flavor.chocolate));
+      expect(Flavor.fromValue(-1), equals(Flavor.unknown));
+      expect(Flavor.fromValue(42), equals(Flavor.unknown));
+    });
+  });
+
   group('Object tests', () {
     test('counter creation and get', () {
       final counter = Counter(0);
@@ -55,7 +145,47 @@ void main() {
     });
   });
 
+  group('Dispose tests', () {
+    test('isDisposed follows dispose', () {
+      final counter = Counter(1);
+      expect(counter.isDisposed, isFalse);
+      counter.dispose();
+      expect(counter.isDisposed, isTrue);
+    });
+
+    test('dispose is idempotent', () {
+      final counter = Counter(1);
+      counter.dispose();
+      counter.dispose();
+      expect(counter.isDisposed, isTrue);
+    });
+
+    test('using a disposed object throws a StateError', () {
+      final counter = Counter(1);
+      counter.dispose();
+      final disposed = throwsA(isA<StateError>()
+          .having((e) => e.message, 'message', contains('Counter')));
+      expect(() => counter.getValue(), disposed);
+      expect(() => counter.increment(), disposed);
+      expect(() => counter.uniffiClonePointer(), disposed);
+      expect(() => Counter.lower(counter), disposed);
+    });
+  });
+
   group('Default parameter tests', () {
+    test('constructor with default argument', () {
+      expect(Counter.startingAt().getValue(), equals(100));
+      expect(Counter.startingAt(initial: 3).getValue(), equals(3));
+    });
+
+    test('method with default argument', () {
+      final counter = Counter(0);
+      counter.incrementBy();
+      expect(counter.getValue(), equals(10));
+      counter.incrementBy(step: 2);
+      expect(counter.getValue(), equals(12));
+    });
+
     test('hash with all defaults', () {
       final data = Uint8List.fromList([1, 2, 3, 4, 5]);
       final result = hashData(data);
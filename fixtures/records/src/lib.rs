@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+uniffi::setup_scaffolding!();
+
+#[derive(uniffi::Record)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(uniffi::Record)]
+pub struct Shape {
+    pub name: String,
+    pub label: Option<String>,
+    pub points: Vec<Point>,
+    pub tags: HashMap<String, Vec<u8>>,
+    pub checksum: Vec<u8>,
+    pub origin: Point,
+}
+
+#[uniffi::export]
+pub fn make_shape() -> Shape {
+    Shape {
+        name: "triangle".to_string(),
+        label: Some("first".to_string()),
+        points: vec![
+            Point { x: 0, y: 0 },
+            Point { x: 1, y: 0 },
+            Point { x: 0, y: 1 },
+        ],
+        tags: HashMap::from([("colour".to_string(), vec![255, 0, 0])]),
+        checksum: vec![1, 2, 3],
+        origin: Point { x: 0, y: 0 },
+    }
+}
+
+#[uniffi::export]
+pub fn echo_shape(shape: Shape) -> Shape {
+    shape
+}
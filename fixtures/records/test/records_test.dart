@@ -0,0 +1,55 @@
+import 'dart:typed_data';
+
+import 'package:test/test.dart';
+import '../records.dart';
+
+Shape expectedShape() => Shape(
+      "triangle",
+      "first",
+      [Point(0, 0), Point(1, 0), Point(0, 1)],
+      {
+        "colour": Uint8List.fromList([255, 0, 0])
+      },
+      Uint8List.fromList([1, 2, 3]),
+      Point(0, 0),
+    );
+
+void main() {
+  test('records compare by value', () {
+    expect(Point(1, 2), equals(Point(1, 2)));
+    expect(Point(1, 2), isNot(equals(Point(2, 1))));
+    expect(Point(1, 2).hashCode, Point(1, 2).hashCode);
+  });
+
+  test('collections inside records compare element by element', () {
+    final shape = makeShape();
+    expect(shape, equals(expectedShape()));
+    expect(shape.hashCode, expectedShape().hashCode);
+    expect(echoShape(shape), equals(shape));
+    expect(shape, isNot(equals(shape.copyWith(checksum: Uint8List(3)))));
+  });
+
+  test('records work as set elements and map keys', () {
+    expect({Point(1, 2), Point(1, 2), Point(3, 4)}, hasLength(2));
+    final names = {Point(0, 0): "origin"};
+    expect(names[Point(0, 0)], "origin");
+  });
+
+  test('toString lists the fields', () {
+    expect(Point(1, 2).toString(), "Point(x: 1, y: 2)");
+  });
+
+  test('copyWith replaces only the given fields', () {
+    final shape = makeShape();
+    final renamed = shape.copyWith(name: "square");
+    expect(renamed.name, "square");
+    expect(renamed.label, "first");
+    expect(renamed.points, shape.points);
+  });
+
+  test('copyWith can clear nullable fields', () {
+    final shape = makeShape();
+    expect(shape.copyWith(label: null).label, isNull);
+    expect(shape.copyWith().label, "first");
+  });
+}
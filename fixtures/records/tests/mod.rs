@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn records() -> Result<()> {
+    uniffi_dart::testing::run_library_mode_test("records", None)
+}
@@ -79,3 +79,4 @@ impl Hash for MyHashSet {
 }
 
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
@@ -24,3 +24,4 @@ pub fn make_object(inner: i32) -> Arc<Object> {
 }
 
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
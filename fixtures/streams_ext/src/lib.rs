@@ -340,3 +340,4 @@ mod tests {
 }
 
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
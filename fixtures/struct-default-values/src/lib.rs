@@ -1,9 +1,26 @@
+use std::collections::HashMap;
+
 pub struct Bookmark {
     pub guid: Option<String>,
     pub position: i32,
     pub last_modified: Option<i32>,
     pub url: String,
     pub title: Option<String>,
+    pub rating: u8,
+    pub starred: bool,
+    pub tags: Vec<String>,
+    pub metadata: HashMap<String, String>,
+}
+
+fn describe(bookmark: Bookmark) -> String {
+    format!(
+        "{} at {} rated {}{} with {} tags",
+        bookmark.url,
+        bookmark.position,
+        bookmark.rating,
+        if bookmark.starred { " (starred)" } else { "" },
+        bookmark.tags.len()
+    )
 }
 
 uniffi::include_scaffolding!("api");
@@ -12,6 +12,20 @@ void main() {
       expect(bookmark.url, equals(url));
       expect(bookmark.lastModified, isNull);
       expect(bookmark.title, isNull);
+      expect(bookmark.rating, equals(5));
+      expect(bookmark.starred, isFalse);
+      expect(bookmark.tags, isEmpty);
+      expect(bookmark.metadata, isEmpty);
+    });
+
+    test('defaults reach Rust', () {
+      const url = "https://mozilla.github.io/uniffi-rs";
+      expect(describe(Bookmark(position: 2, url: url)),
+          equals("$url at 2 rated 5 with 0 tags"));
+      expect(
+          describe(Bookmark(
+              position: 1, url: url, rating: 3, starred: true, tags: ["a"])),
+          equals("$url at 1 rated 3 (starred) with 1 tags"));
     });
 
     test('bookmark others set', () {
@@ -0,0 +1,74 @@
+use std::thread;
+
+trait Logger: Send + Sync {
+    fn log(&self, message: String);
+}
+
+trait Delegate: Send + Sync {
+    fn compute(&self, value: i32) -> i32;
+    fn describe(&self, value: i32) -> Result<String, DelegateError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DelegateError {
+    #[error("Refused")]
+    Refused,
+    #[error("Unexpected")]
+    Unexpected,
+}
+
+impl From<uniffi::UnexpectedUniFFICallbackError> for DelegateError {
+    fn from(_: uniffi::UnexpectedUniFFICallbackError) -> DelegateError {
+        DelegateError::Unexpected
+    }
+}
+
+// Every call below happens on a thread Dart has never seen, which is what the thread-safe
+// dispatch mode is for.
+
+fn log_from_threads(logger: Box<dyn Logger>, count: u32) {
+    let logger = &*logger;
+    thread::scope(|scope| {
+        for i in 0..count {
+            scope.spawn(move || logger.log(format!("message {i}")));
+        }
+    });
+}
+
+// The Dart side is idle while the delegate runs, so it is free to answer the blocked thread.
+fn compute_in_background(delegate: Box<dyn Delegate>, value: i32, reporter: Box<dyn Logger>) {
+    thread::spawn(move || reporter.log(delegate.compute(value).to_string()));
+}
+
+fn describe_in_background(delegate: Box<dyn Delegate>, value: i32, reporter: Box<dyn Logger>) {
+    thread::spawn(move || {
+        let message = match delegate.describe(value) {
+            Ok(description) => description,
+            Err(e) => format!("error: {e}"),
+        };
+        reporter.log(message);
+    });
+}
+
+// Called on the thread the isolate is blocked on, so there is nobody else to answer the call.
+fn compute_here(delegate: Box<dyn Delegate>, value: i32) -> i32 {
+    delegate.compute(value)
+}
+
+// The isolate stays blocked in this call until the other thread is done, so it cannot answer
+// it. Instead of waiting forever, the other thread gets an error.
+fn describe_while_blocked(delegate: Box<dyn Delegate>, value: i32) -> String {
+    let delegate = &*delegate;
+    thread::scope(|scope| {
+        scope
+            .spawn(|| match delegate.describe(value) {
+                Ok(description) => description,
+                Err(e) => format!("error: {e}"),
+            })
+            .join()
+            .unwrap()
+    })
+}
+
+uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
@@ -0,0 +1,93 @@
+import 'dart:async';
+import 'dart:isolate';
+
+import 'package:test/test.dart';
+import '../thread_safe_callbacks.dart';
+
+class CollectingLogger extends Logger {
+  final messages = <String>[];
+  final _done = Completer<void>();
+  final int expected;
+
+  CollectingLogger(this.expected);
+
+  Future<void> get done => _done.future;
+
+  @override
+  void log(String message) {
+    messages.add(message);
+    if (messages.length == expected) {
+      _done.complete();
+    }
+  }
+}
+
+class Doubler extends Delegate {
+  @override
+  int compute(int value) => value * 2;
+
+  @override
+  String describe(int value) {
+    if (value < 0) {
+      throw RefusedDelegateException();
+    }
+    if (value == 0) {
+      throw StateError("zero");
+    }
+    return "value $value";
+  }
+}
+
+void main() {
+  test('void methods called from other threads reach the isolate', () async {
+    final logger = CollectingLogger(8);
+    logFromThreads(logger, 8);
+    await logger.done.timeout(const Duration(seconds: 5));
+    expect(logger.messages.toSet(),
+        {for (var i = 0; i < 8; i++) "message $i"});
+  });
+
+  Future<String> reported(void Function(Logger reporter) start) async {
+    final reporter = CollectingLogger(1);
+    start(reporter);
+    await reporter.done.timeout(const Duration(seconds: 5));
+    return reporter.messages.single;
+  }
+
+  test('returning methods block the calling thread for the answer', () async {
+    expect(await reported((r) => computeInBackground(Doubler(), 21, r)), "42");
+    expect(await reported((r) => describeInBackground(Doubler(), 7, r)),
+        "value 7");
+  });
+
+  test('errors thrown on the isolate are returned to the calling thread',
+      () async {
+    expect(await reported((r) => describeInBackground(Doubler(), -1, r)),
+        "error: Refused");
+    expect(await reported((r) => describeInBackground(Doubler(), 0, r)),
+        "error: Unexpected");
+  });
+
+  test('returning methods called on the isolate\'s own thread run directly',
+      () {
+    expect(computeHere(Doubler(), 21), 42);
+  });
+
+  test('calls from other threads fail while the isolate is blocked in Rust',
+      () {
+    expect(describeWhileBlocked(Doubler(), 7), "error: Unexpected");
+  });
+
+  test('implementations are only registered by one isolate', () async {
+    computeHere(Doubler(), 1);
+    final error = await Isolate.run(() {
+      try {
+        computeHere(Doubler(), 1);
+        return null;
+      } on StateError catch (e) {
+        return e.message;
+      }
+    });
+    expect(error, contains("registered by another isolate"));
+  });
+}
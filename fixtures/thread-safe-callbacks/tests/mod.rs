@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn thread_safe_callbacks() -> Result<()> {
+    uniffi_dart::testing::run_test("thread_safe_callbacks", "src/api.udl", Some("uniffi.toml"))
+}
@@ -2,6 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
 
 use chrono::offset::Utc;
@@ -68,6 +69,52 @@ fn set_seconds_before_unix_epoch(seconds: u64) -> Result<SystemTime> {
         .ok_or(ChronologicalError::TimeOverflow { a, b })
 }
 
+pub struct TimeWindow {
+    pub start: SystemTime,
+    pub end: Option<SystemTime>,
+    pub checkpoints: Vec<SystemTime>,
+    pub labels: HashMap<String, SystemTime>,
+}
+
+pub enum TimeEvent {
+    Started { at: SystemTime },
+    Stopped { at: SystemTime, elapsed: Duration },
+    Scheduled { at: Option<SystemTime> },
+}
+
+pub trait TimeSource: Send + Sync {
+    fn current_time(&self) -> SystemTime;
+    fn shift(&self, a: SystemTime, seconds: i64) -> SystemTime;
+}
+
+fn return_time_window(window: TimeWindow) -> TimeWindow {
+    window
+}
+
+fn return_time_event(event: TimeEvent) -> TimeEvent {
+    event
+}
+
+fn return_optional_timestamp(a: Option<SystemTime>) -> Option<SystemTime> {
+    a
+}
+
+fn return_timestamps(a: Vec<SystemTime>) -> Vec<SystemTime> {
+    a
+}
+
+fn return_timestamp_map(a: HashMap<String, SystemTime>) -> HashMap<String, SystemTime> {
+    a
+}
+
+fn query_time_source(source: Box<dyn TimeSource>) -> SystemTime {
+    source.current_time()
+}
+
+fn shift_with_time_source(source: Box<dyn TimeSource>, a: SystemTime, seconds: i64) -> SystemTime {
+    source.shift(a, seconds)
+}
+
 type Result<T, E = ChronologicalError> = std::result::Result<T, E>;
 
 uniffi::include_scaffolding!("api");
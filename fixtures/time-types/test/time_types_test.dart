@@ -1,11 +1,23 @@
 import 'package:test/test.dart';
 import '../time_types.dart';
 
+class FixedTimeSource implements TimeSource {
+  final DateTime fixed;
+
+  FixedTimeSource(this.fixed);
+
+  @override
+  DateTime currentTime() => fixed;
+
+  @override
+  DateTime shift(DateTime a, int seconds) => a.add(Duration(seconds: seconds));
+}
+
 void main() {
   group('Time Types', () {
     test('basic timestamp operations', () {
       // Test returning timestamps
-      final now = timeTypesNow();
+      final now = now();
       final returned = returnTimestamp(now);
       expect(returned, equals(now));
     });
@@ -19,7 +31,7 @@ void main() {
 
     test('timestamp string conversion', () {
       // Test converting timestamps to ISO 8601 strings
-      final timestamp = timeTypesNow();
+      final timestamp = now();
       final timeString = toStringTimestamp(timestamp);
 
       // Should be in ISO 8601 format with microseconds and Z
@@ -39,7 +51,7 @@ void main() {
 
     test('timestamp arithmetic', () {
       // Test adding duration to timestamp
-      final baseTime = timeTypesNow();
+      final baseTime = now();
       final duration = Duration(hours: 1, minutes: 30);
 
       final result = add(baseTime, duration);
@@ -48,7 +60,7 @@ void main() {
 
     test('timestamp difference calculation', () {
       // Test calculating difference between timestamps
-      final time1 = timeTypesNow();
+      final time1 = now();
       final duration = Duration(minutes: 45);
       final time2 = add(time1, duration);
 
@@ -58,7 +70,7 @@ void main() {
 
     test('timestamp equality', () {
       // Test timestamp equality comparison
-      final time1 = timeTypesNow();
+      final time1 = now();
       final time2 = time1; // Same reference
       final time3 = add(time1, Duration(seconds: 1));
 
@@ -68,7 +80,7 @@ void main() {
 
     test('optional timestamp and duration', () {
       // Test optional timestamp and duration parameters
-      final timestamp = timeTypesNow();
+      final timestamp = now();
       final duration = Duration(seconds: 30);
 
       expect(optional(timestamp, duration), isTrue);
@@ -98,21 +110,21 @@ void main() {
         );
         final hugeDuration = Duration(days: 365 * 1000); // 1000 years
         add(maxTime, hugeDuration);
-      }, throwsA(isA<ChronologicalError>()));
+      }, throwsA(isA<ChronologicalException>()));
     });
 
     test('error handling - time difference error', () {
       // Test error handling for time difference calculations
       expect(() {
-        final time1 = timeTypesNow();
+        final time1 = now();
         final time2 = add(time1, Duration(hours: -1)); // Earlier time
         diff(time2, time1); // Should fail: time2 is before time1
-      }, throwsA(isA<ChronologicalError>()));
+      }, throwsA(isA<ChronologicalException>()));
     });
 
     test('comprehensive timestamp workflow', () {
       // Test a complete workflow with various time operations
-      final startTime = timeTypesNow();
+      final startTime = now();
 
       // Add some time
       final afterOneHour = add(startTime, Duration(hours: 1));
@@ -149,4 +161,66 @@ void main() {
       expect(returnedLarge, equals(largeDuration));
     });
   });
+
+  group('Timestamps in compound types', () {
+    final start = DateTime.utc(2024, 2, 29, 12, 30, 15, 123, 456);
+    final preEpoch = DateTime.utc(1969, 7, 20, 20, 17, 40, 0, 1);
+
+    test('microsecond precision round trip', () {
+      expect(returnTimestamp(start), equals(start));
+      expect(returnTimestamp(start).microsecond, equals(456));
+    });
+
+    test('pre-epoch round trip', () {
+      expect(returnTimestamp(preEpoch), equals(preEpoch));
+    });
+
+    test('records', () {
+      final window = TimeWindow(
+        start,
+        null,
+        [start, preEpoch],
+        {'launch': preEpoch},
+      );
+      final returned = returnTimeWindow(window);
+      expect(returned.start, equals(start));
+      expect(returned.end, isNull);
+      expect(returned.checkpoints, equals([start, preEpoch]));
+      expect(returned.labels, equals({'launch': preEpoch}));
+    });
+
+    test('enums', () {
+      final started = returnTimeEvent(StartedTimeEvent(start));
+      expect((started as StartedTimeEvent).at, equals(start));
+
+      final stopped = returnTimeEvent(
+        StoppedTimeEvent(at: preEpoch, elapsed: Duration(seconds: 5)),
+      );
+      expect((stopped as StoppedTimeEvent).at, equals(preEpoch));
+      expect(stopped.elapsed, equals(Duration(seconds: 5)));
+
+      final scheduled = returnTimeEvent(ScheduledTimeEvent(null));
+      expect((scheduled as ScheduledTimeEvent).at, isNull);
+    });
+
+    test('optionals, sequences and maps', () {
+      expect(returnOptionalTimestamp(start), equals(start));
+      expect(returnOptionalTimestamp(null), isNull);
+      expect(returnTimestamps([start, preEpoch]), equals([start, preEpoch]));
+      expect(returnTimestamps([]), isEmpty);
+      expect(
+        returnTimestampMap({'start': start, 'before': preEpoch}),
+        equals({'start': start, 'before': preEpoch}),
+      );
+    });
+
+    test('callback arguments and returns', () {
+      final source = FixedTimeSource(preEpoch);
+      expect(queryTimeSource(source), equals(preEpoch));
+      expect(
+        shiftWithTimeSource(source, start, 90),
+        equals(start.add(Duration(seconds: 90))),
+      );
+    });
+  });
 }
@@ -1,7 +1,6 @@
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn test_time_types() {
-        uniffi_dart::testing::run_test("time_types", None).unwrap();
-    }
+use anyhow::Result;
+
+#[test]
+fn time_types() -> Result<()> {
+    uniffi_dart::testing::run_test("time-types", "src/api.udl", None)
 }
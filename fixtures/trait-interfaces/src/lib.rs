@@ -93,4 +93,62 @@ impl Registry {
     }
 }
 
+/// A trait that can be implemented in Rust or in Dart.
+#[uniffi::export(with_foreign)]
+pub trait Calculator: Send + Sync {
+    fn add(&self, a: u32, b: u32) -> u32;
+    fn name(&self) -> String;
+}
+
+struct RustCalculator;
+
+impl Calculator for RustCalculator {
+    fn add(&self, a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    fn name(&self) -> String {
+        "rust".to_string()
+    }
+}
+
+#[uniffi::export]
+pub fn make_rust_calculator() -> Arc<dyn Calculator> {
+    Arc::new(RustCalculator)
+}
+
+#[uniffi::export]
+pub fn calculate_with(calculator: Arc<dyn Calculator>, a: u32, b: u32) -> String {
+    format!("{}: {}", calculator.name(), calculator.add(a, b))
+}
+
+#[uniffi::export]
+pub fn round_trip_calculator(calculator: Arc<dyn Calculator>) -> Arc<dyn Calculator> {
+    calculator
+}
+
+#[derive(uniffi::Object)]
+pub struct CalculatorHolder {
+    calculator: Arc<dyn Calculator>,
+}
+
+#[uniffi::export]
+impl CalculatorHolder {
+    #[uniffi::constructor]
+    pub fn new(calculator: Arc<dyn Calculator>) -> Arc<Self> {
+        Arc::new(Self { calculator })
+    }
+
+    pub fn calculator(&self) -> Arc<dyn Calculator> {
+        self.calculator.clone()
+    }
+
+    pub fn sum(&self, values: Vec<u32>) -> u32 {
+        values
+            .into_iter()
+            .fold(0, |acc, value| self.calculator.add(acc, value))
+    }
+}
+
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
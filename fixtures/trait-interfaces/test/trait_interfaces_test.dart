@@ -38,4 +38,47 @@ void main() {
       proc.dispose();
     });
   });
+
+  group('Calculator (with_foreign)', () {
+    test('Rust implementations are usable from Dart', () {
+      final calculator = makeRustCalculator();
+      expect(calculator.add(2, 3), equals(5));
+      expect(calculator.name(), equals('rust'));
+      expect(calculateWith(calculator, 1, 1), equals('rust: 2'));
+      calculator.dispose();
+    });
+
+    test('Dart implementations are usable from Rust', () {
+      final calculator = DoublingCalculator();
+      expect(calculateWith(calculator, 2, 3), equals('dart: 10'));
+    });
+
+    test('Dart implementations keep their identity across a round trip', () {
+      final calculator = DoublingCalculator();
+      expect(identical(roundTripCalculator(calculator), calculator), isTrue);
+    });
+
+    test('Rust implementations survive a round trip', () {
+      final calculator = roundTripCalculator(makeRustCalculator());
+      expect(calculator.add(4, 5), equals(9));
+      calculator.dispose();
+    });
+
+    test('objects can hold Dart implementations', () {
+      final calculator = DoublingCalculator();
+      final holder = CalculatorHolder(calculator);
+      expect(holder.sum([1, 2, 3]), equals(22));
+      expect(identical(holder.calculator(), calculator), isTrue);
+      expect(identical(holder.calculator(), calculator), isTrue);
+      holder.dispose();
+    });
+  });
+}
+
+class DoublingCalculator extends Calculator {
+  @override
+  int add(int a, int b) => (a + b) * 2;
+
+  @override
+  String name() => 'dart';
 }
@@ -17,8 +17,8 @@ impl std::fmt::Display for TraitMethods {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, uniffi::Object)]
-#[uniffi::export(Debug, Display, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, uniffi::Object)]
+#[uniffi::export(Debug, Display, Eq, Hash, Ord)]
 pub struct ProcTraitMethods {
     val: String,
 }
@@ -37,4 +37,85 @@ impl std::fmt::Display for ProcTraitMethods {
     }
 }
 
+/// Tags compare, hash and sort ignoring case, which Dart can only get right by asking Rust.
+#[derive(Debug, uniffi::Record)]
+#[uniffi::export(Debug, Display, Eq, Hash, Ord)]
+pub struct Tag {
+    pub name: String,
+}
+
+impl Tag {
+    fn key(&self) -> String {
+        self.name.to_lowercase()
+    }
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Tag {}
+
+impl std::hash::Hash for Tag {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl PartialOrd for Tag {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tag {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, uniffi::Enum)]
+#[uniffi::export(Debug, Display, Eq, Hash, Ord)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        };
+        write!(f, "{name} priority")
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, uniffi::Enum)]
+#[uniffi::export(Display, Eq, Hash, Ord)]
+pub enum Measurement {
+    Length { metres: u32 },
+    Weight { grams: u32 },
+}
+
+impl std::fmt::Display for Measurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Measurement::Length { metres } => write!(f, "{metres}m"),
+            Measurement::Weight { grams } => write!(f, "{grams}g"),
+        }
+    }
+}
+
 uniffi::include_scaffolding!("api");
+uniffi_dart_runtime::setup!();
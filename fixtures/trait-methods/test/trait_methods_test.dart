@@ -51,5 +51,63 @@ void main() {
       expect(m.toString(), equals("ProcTraitMethods(yo)"));
       expect(m.debugString(), equals('ProcTraitMethods { val: "yo" }'));
     });
+
+    test('ord trait (comparison)', () {
+      final a = ProcTraitMethods("a");
+      final b = ProcTraitMethods("b");
+
+      expect(a.compareTo(b), lessThan(0));
+      expect(a.compareTo(ProcTraitMethods("a")), 0);
+      expect(a < b, isTrue);
+      expect(a <= ProcTraitMethods("a"), isTrue);
+      expect(b > a, isTrue);
+      expect(a >= b, isFalse);
+
+      final sorted = [ProcTraitMethods("c"), b, a]..sort();
+      expect(sorted.map((m) => m.toString()), [
+        "ProcTraitMethods(a)",
+        "ProcTraitMethods(b)",
+        "ProcTraitMethods(c)",
+      ]);
+    });
+  });
+
+  group('Record traits', () {
+    test('equality and hashing follow Rust', () {
+      expect(Tag("Rust"), equals(Tag("rust")));
+      expect(Tag("Rust").hashCode, Tag("RUST").hashCode);
+      expect(Tag("Rust"), isNot(equals(Tag("Dart"))));
+      expect({Tag("Rust"), Tag("rust"), Tag("Dart")}, hasLength(2));
+    });
+
+    test('display and debug', () {
+      expect(Tag("Rust").toString(), "#Rust");
+      expect(Tag("Rust").debugString(), 'Tag { name: "Rust" }');
+    });
+
+    test('ordering', () {
+      final tags = [Tag("b"), Tag("C"), Tag("a")]..sort();
+      expect(tags.map((t) => t.name), ["a", "b", "C"]);
+      expect(Tag("A").compareTo(Tag("a")), 0);
+    });
+  });
+
+  group('Enum traits', () {
+    test('flat enums', () {
+      expect(Priority.high.toString(), "high priority");
+      expect(Priority.low.debugString(), "Low");
+      expect(Priority.low.compareTo(Priority.high), lessThan(0));
+      expect(([Priority.high, Priority.low, Priority.medium]..sort()),
+          [Priority.low, Priority.medium, Priority.high]);
+    });
+
+    test('enums with fields', () {
+      expect(LengthMeasurement(3).toString(), "3m");
+      expect(WeightMeasurement(5), equals(WeightMeasurement(5)));
+      expect(WeightMeasurement(5).hashCode, WeightMeasurement(5).hashCode);
+      expect(WeightMeasurement(5), isNot(equals(LengthMeasurement(5))));
+      expect(LengthMeasurement(9).compareTo(WeightMeasurement(1)), lessThan(0));
+      expect(LengthMeasurement(1).compareTo(LengthMeasurement(2)), lessThan(0));
+    });
   });
 }
@@ -0,0 +1,136 @@
+uniffi::setup_scaffolding!();
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum ValidationError {
+    #[error("name must not be empty")]
+    EmptyName,
+    #[error("age {age} is out of range")]
+    AgeOutOfRange { age: i32 },
+}
+
+#[derive(uniffi::Record)]
+pub struct Profile {
+    pub name: String,
+    pub age: i32,
+}
+
+#[uniffi::export]
+impl Profile {
+    /// A profile without a name.
+    #[uniffi::constructor]
+    pub fn anonymous(age: i32) -> Self {
+        Profile {
+            name: String::new(),
+            age,
+        }
+    }
+
+    /// Parses `name:age`.
+    #[uniffi::constructor]
+    pub fn parse(text: String) -> Result<Self, ValidationError> {
+        let (name, age) = text.split_once(':').ok_or(ValidationError::EmptyName)?;
+        let age = age
+            .parse()
+            .map_err(|_| ValidationError::AgeOutOfRange { age: -1 })?;
+        let profile = Profile {
+            name: name.to_string(),
+            age,
+        };
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    #[uniffi::constructor]
+    pub async fn newborn(name: String) -> Self {
+        Profile { name, age: 0 }
+    }
+
+    /// The name followed by the age in brackets.
+    pub fn display_name(&self) -> String {
+        format!("{} ({})", self.name, self.age)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.name.is_empty() {
+            return Err(ValidationError::EmptyName);
+        }
+        if !(0..=150).contains(&self.age) {
+            return Err(ValidationError::AgeOutOfRange { age: self.age });
+        }
+        Ok(())
+    }
+
+    pub fn birthday(&self) -> Profile {
+        Profile {
+            name: self.name.clone(),
+            age: self.age + 1,
+        }
+    }
+
+    pub async fn greet(&self, greeting: String) -> String {
+        format!("{greeting}, {}!", self.name)
+    }
+
+    pub async fn validated_name(&self) -> Result<String, ValidationError> {
+        self.validate()?;
+        Ok(self.display_name())
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[uniffi::export]
+impl Direction {
+    #[uniffi::constructor]
+    pub fn from_degrees(degrees: u32) -> Self {
+        match (degrees % 360 + 45) / 90 {
+            0 | 4 => Direction::North,
+            1 => Direction::East,
+            2 => Direction::South,
+            _ => Direction::West,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::North | Direction::South)
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+}
+
+#[uniffi::export]
+impl Shape {
+    #[uniffi::constructor]
+    pub fn square(side: f64) -> Self {
+        Shape::Rectangle {
+            width: side,
+            height: side,
+        }
+    }
+
+    pub fn area(&self) -> f64 {
+        match self {
+            Shape::Circle { radius } => std::f64::consts::PI * radius * radius,
+            Shape::Rectangle { width, height } => width * height,
+        }
+    }
+}
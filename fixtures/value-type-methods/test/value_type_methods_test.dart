@@ -0,0 +1,73 @@
+import 'package:test/test.dart';
+import '../value_type_methods.dart';
+
+void main() {
+  group('record methods', () {
+    test('sync methods see the record fields', () {
+      final profile = Profile("Ada", 36);
+      expect(profile.displayName(), "Ada (36)");
+      expect(profile.birthday(), Profile("Ada", 37));
+    });
+
+    test('throwing methods raise the error type', () {
+      Profile("Ada", 36).validate();
+      expect(() => Profile("", 36).validate(),
+          throwsA(isA<EmptyNameValidationException>()));
+      expect(
+          () => Profile("Ada", 200).validate(),
+          throwsA(isA<AgeOutOfRangeValidationException>()
+              .having((e) => e.age, 'age', 200)));
+    });
+
+    test('async methods', () async {
+      expect(await Profile("Ada", 36).greet("Hello"), "Hello, Ada!");
+      expect(await Profile("Ada", 36).validatedName(), "Ada (36)");
+      expect(() => Profile("", 36).validatedName(),
+          throwsA(isA<EmptyNameValidationException>()));
+    });
+  });
+
+  group('record constructors', () {
+    test('sync constructors', () {
+      expect(Profile.anonymous(7), Profile("", 7));
+    });
+
+    test('throwing constructors raise the error type', () {
+      expect(Profile.parse("Ada:36"), Profile("Ada", 36));
+      expect(() => Profile.parse("Ada"),
+          throwsA(isA<EmptyNameValidationException>()));
+      expect(() => Profile.parse("Ada:200"),
+          throwsA(isA<AgeOutOfRangeValidationException>()));
+    });
+
+    test('async constructors', () async {
+      expect(await Profile.newborn("Ada"), Profile("Ada", 0));
+    });
+  });
+
+  group('enum constructors', () {
+    test('flat enums', () {
+      expect(Direction.fromDegrees(0), Direction.north);
+      expect(Direction.fromDegrees(100), Direction.east);
+      expect(Direction.fromDegrees(350), Direction.north);
+    });
+
+    test('enums with fields', () {
+      expect(Shape.square(3).area(), 9);
+    });
+  });
+
+  group('enum methods', () {
+    test('flat enums', () {
+      expect(Direction.north.turnRight(), Direction.east);
+      expect(Direction.west.turnRight(), Direction.north);
+      expect(Direction.south.isVertical(), isTrue);
+      expect(Direction.east.isVertical(), isFalse);
+    });
+
+    test('enums with fields', () {
+      expect(RectangleShape(width: 2, height: 3).area(), 6);
+      expect(CircleShape(1).area(), closeTo(3.14159, 1e-5));
+    });
+  });
+}
@@ -0,0 +1,6 @@
+use anyhow::Result;
+
+#[test]
+fn value_type_methods() -> Result<()> {
+    uniffi_dart::testing::run_library_mode_test("value_type_methods", None)
+}
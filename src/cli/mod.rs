@@ -118,15 +118,15 @@ fn generate_library_mode(
     std::fs::create_dir_all(out_dir)
         .with_context(|| format!("Failed to create output directory: {}", out_dir))?;
 
-    let config_supplier: Box<dyn BindgenCrateConfigSupplier> =
-        Box::new(uniffi_bindgen::EmptyCrateConfigSupplier {});
+    let config_supplier: Box<dyn BindgenCrateConfigSupplier> = match config_path {
+        Some(config_path) => Box::new(crate::gen::ConfigFileSupplier(config_path.to_string())),
+        None => Box::new(uniffi_bindgen::EmptyCrateConfigSupplier {}),
+    };
 
-    uniffi_bindgen::library_mode::generate_bindings(
+    crate::gen::generate_library_bindings(
         library_path,
-        crate_filter,
-        &crate::gen::DartBindingGenerator {},
+        crate_filter.as_deref(),
         config_supplier.as_ref(),
-        config_path,
         out_dir,
         format,
     )?;
@@ -151,7 +151,9 @@ fn generate_udl_mode(
     let library_path = find_library_for_udl(udl_path);
 
     uniffi_bindgen::generate_external_bindings(
-        &crate::gen::DartBindingGenerator {},
+        &crate::gen::DartBindingGenerator {
+            library: library_path.clone(),
+        },
         udl_path,
         config_path,
         Some(out_dir),
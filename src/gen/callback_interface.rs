@@ -1,7 +1,8 @@
+use crate::gen::CallbackDispatch;
 use crate::gen::CodeType;
 use genco::prelude::*;
 use uniffi_bindgen::interface::Type;
-use uniffi_bindgen::interface::{AsType, Method};
+use uniffi_bindgen::interface::{AsType, FfiType, Method};
 
 use crate::gen::oracle::{AsCodeType, DartCodeOracle};
 use crate::gen::render::AsRenderable;
@@ -44,6 +45,7 @@ impl Renderable for CallbackInterfaceCodeType {
         // Generate all necessary components for the callback interface
         let interface = generate_callback_interface(
             callback.name(),
+            callback.docstring(),
             &callback.as_codetype().ffi_converter_name(),
             &callback.methods(),
             type_helper,
@@ -62,6 +64,7 @@ impl Renderable for CallbackInterfaceCodeType {
             callback.name(),
             &callback.methods(),
             &ffi_module,
+            type_helper,
         );
 
         quote! {
@@ -75,6 +78,7 @@ impl Renderable for CallbackInterfaceCodeType {
 
 pub fn generate_callback_interface(
     callback_name: &str,
+    docstring: Option<&str>,
     ffi_converter_name: &str,
     methods: &[&Method],
     type_helper: &dyn TypeHelperRenderer,
@@ -85,6 +89,7 @@ pub fn generate_callback_interface(
 
     let tokens = quote! {
         // This is the abstract class to be implemented
+        $(DartCodeOracle::docstring(docstring))
         abstract class $cls_name {
             $(for m in methods {
                 $(generate_callback_methods_definitions(m, type_helper))
@@ -93,7 +98,7 @@ pub fn generate_callback_interface(
 
         // This is the type helper to convert from FFI to Dart
         class $ffi_conv_name {
-            static final _handleMap = UniffiHandleMap<$cls_name>();
+            static final _handleMap = UniffiHandleMap<$cls_name>($(format!("\"{cls_name}\"")));
             static bool _vtableInitialized = false;
 
             static $cls_name lift(Pointer<Void> handle) {
@@ -137,7 +142,7 @@ pub fn generate_callback_interface(
     tokens
 }
 
-fn generate_callback_methods_definitions(
+pub fn generate_callback_methods_definitions(
     method: &Method,
     type_helper: &dyn TypeHelperRenderer,
 ) -> dart::Tokens {
@@ -153,18 +158,22 @@ fn generate_callback_methods_definitions(
         })
         .collect::<Vec<_>>();
 
-    let ret_type = if let Some(ret) = method.return_type() {
+    let mut ret_type = if let Some(ret) = method.return_type() {
         ret.as_renderable().render_type(ret, type_helper)
     } else {
         quote!(void)
     };
+    if method.is_async() {
+        ret_type = quote!(Future<$ret_type>);
+    }
 
     quote!(
+        $(DartCodeOracle::docstring(method.docstring()))
         $ret_type $method_name($(for a in dart_args => $a,));
     )
 }
 
-fn generate_callback_methods_signatures(
+pub fn generate_callback_methods_signatures(
     callback_name: &str,
     methods: &[&Method],
     type_helper: &dyn TypeHelperRenderer,
@@ -178,6 +187,22 @@ fn generate_callback_methods_signatures(
         let dart_method_type =
             format!("UniffiCallbackInterface{callback_name}Method{method_index}Dart");
 
+        if method.is_async() {
+            let complete_type = format!(
+                "UniffiForeignFutureComplete{}",
+                DartCodeOracle::foreign_future_suffix(method.return_type())
+            );
+            tokens.append(quote! {
+                typedef $ffi_method_type = Void Function(
+                    Uint64, $(for arg in &method.arguments() => $(DartCodeOracle::native_type_label(Some(&arg.as_type()), type_helper.get_ci())),)
+                    Pointer<NativeFunction<$(&complete_type)>>, Uint64, Pointer<UniffiForeignFutureDroppedCallbackStruct>);
+                typedef $dart_method_type = void Function(
+                    int, $(for arg in &method.arguments() => $(DartCodeOracle::native_dart_type_label(Some(&arg.as_type()), type_helper.get_ci())),)
+                    Pointer<NativeFunction<$(&complete_type)>>, int, Pointer<UniffiForeignFutureDroppedCallbackStruct>);
+            });
+            continue;
+        }
+
         let method_return_type = if let Some(ret) = method.return_type() {
             DartCodeOracle::native_type_label(Some(ret), type_helper.get_ci())
         } else {
@@ -228,17 +253,22 @@ pub fn generate_callback_functions(
     type_helper: &dyn TypeHelperRenderer,
 ) -> dart::Tokens {
     let cls_name = &DartCodeOracle::class_name(callback_name);
+    let thread_safe =
+        type_helper.get_config().callback_dispatch(callback_name) == CallbackDispatch::ThreadSafe;
+    let dispatch_cls = &format!("_UniffiCallbackInterface{cls_name}Dispatch");
 
+    let mut shared_addresses: Vec<String> = Vec::new();
     let functions: Vec<dart::Tokens> = methods.iter().enumerate().map(|(index, m)| {
         let method_name = &DartCodeOracle::fn_name(m.name()).to_string();
         let ffi_method_type = &format!("UniffiCallbackInterface{callback_name}Method{index}");
-        let _dart_method_type = &format!("UniffiCallbackInterface{callback_name}Method{index}Dart");
+        let dart_method_type = &format!("UniffiCallbackInterface{callback_name}Method{index}Dart");
 
         // Get parameter types using the oracle
         let param_types: Vec<dart::Tokens> = m.arguments().iter().map(|arg| {
             let arg_name = DartCodeOracle::var_name(arg.name());
             DartCodeOracle::callback_param_type(&arg.as_type(), &arg_name, type_helper.get_ci())
         }).collect();
+        let param_names: Vec<String> = m.arguments().iter().map(|arg| DartCodeOracle::var_name(arg.name())).collect();
 
         // Get argument lifts using the oracle
         let arg_lifts: Vec<dart::Tokens> = m.arguments().iter().enumerate().map(|(arg_idx, arg)| {
@@ -251,36 +281,148 @@ pub fn generate_callback_functions(
             DartCodeOracle::callback_arg_name(&arg.as_type(), arg_idx)
         }).collect();
 
+        let callback_method_name = &format!("{}{}", &DartCodeOracle::fn_name(callback_name), &DartCodeOracle::class_name(m.name()));
+
+        if m.is_async() {
+            let trampoline = generate_async_callback_function(
+                m,
+                type_helper,
+                cls_name,
+                callback_method_name,
+                param_types,
+                arg_lifts,
+                arg_names,
+            );
+            // The trampoline only hands out work to the event loop and reports back through the
+            // completion callback, which Rust accepts from any thread
+            let pointer = if thread_safe {
+                listener_pointer(ffi_method_type, callback_method_name, callback_method_name)
+            } else {
+                quote! {
+                    final Pointer<NativeFunction<$ffi_method_type>> $(callback_method_name)Pointer =
+                        Pointer.fromFunction<$ffi_method_type>($callback_method_name);
+                }
+            };
+            return quote! {
+                $trampoline
+
+                $pointer
+            };
+        }
+
         // Handle return value using the oracle
         let call_dart_method = if let Some(ret) = m.return_type() {
-            DartCodeOracle::callback_return_handling(ret, method_name, arg_names)
+            DartCodeOracle::callback_return_handling(ret, method_name, arg_names.clone())
         } else {
             // Handle void return types
-            DartCodeOracle::callback_void_handling(method_name, arg_names)
+            DartCodeOracle::callback_void_handling(method_name, arg_names.clone())
         };
 
         // Get the appropriate out return type
-        let out_return_type = DartCodeOracle::callback_out_return_type(m.return_type());
-
-        // Generate the function body
-        let callback_method_name = &format!("{}{}", &DartCodeOracle::fn_name(callback_name), &DartCodeOracle::class_name(m.name()));
+        let out_return_type = &DartCodeOracle::callback_out_return_type(m.return_type());
 
-        quote! {
+        let (receive_buffers, hand_over_buffers) = callback_buffer_tracking(m, quote!(status), type_helper);
+        let trampoline = quote! {
             void $callback_method_name(int uniffiHandle, $(for param in &param_types => $param,) $out_return_type outReturn, Pointer<RustCallStatus> callStatus) {
                 final status = callStatus.ref;
+                $(&receive_buffers)
                 try {
                     final obj = FfiConverterCallbackInterface$cls_name._handleMap.get(uniffiHandle);
-                    $(arg_lifts)
+                    $(&arg_lifts)
                     $call_dart_method
-                } catch (e) {
+                } $(DartCodeOracle::callback_error_catch(m.throws_type(), quote!(status))) catch (e) {
                     status.code = CALL_UNEXPECTED_ERROR;
                     status.errorBuf = FfiConverterString.lower(e.toString());
                 }
+                $(&hand_over_buffers)
             }
+        };
 
-            final Pointer<NativeFunction<$ffi_method_type>> $(callback_method_name)Pointer =
-                Pointer.fromFunction<$ffi_method_type>($callback_method_name);
+        if !thread_safe {
+            return quote! {
+                $trampoline
+
+                final Pointer<NativeFunction<$ffi_method_type>> $(callback_method_name)Pointer =
+                    Pointer.fromFunction<$ffi_method_type>($callback_method_name);
+            };
+        }
+
+        if m.return_type().is_none() && m.throws_type().is_none() {
+            // Nothing flows back to Rust, so the call is posted to the isolate and Rust carries
+            // on straight away. `outReturn` and `callStatus` are gone by the time this runs.
+            let listener_name = &format!("{callback_method_name}Listener");
+            return quote! {
+                void $listener_name(int uniffiHandle, $(for param in &param_types => $param,) $out_return_type outReturn, Pointer<RustCallStatus> callStatus) {
+                    $(&receive_buffers)
+                    try {
+                        final obj = FfiConverterCallbackInterface$cls_name._handleMap.get(uniffiHandle);
+                        $(&arg_lifts)
+                        obj.$method_name($(for arg in &arg_names => $arg,));
+                    } catch (e, s) {
+                        Zone.current.handleUncaughtError(e, s);
+                    }
+                }
+
+                $(listener_pointer(ffi_method_type, listener_name, callback_method_name))
+            };
         }
+
+        // Rust needs an answer. Called on the thread the isolate is blocked on, the trampoline
+        // runs right away, otherwise the calling thread waits until the isolate has run it. If
+        // the isolate is blocked in a Rust call on another thread, Rust gets an error instead.
+        let posted_name = &format!("{callback_method_name}Posted");
+        let blocking_name = &format!("{callback_method_name}Blocking");
+        let posted_type = &format!("{ffi_method_type}Posted");
+        let posted_dart_type = &format!("{ffi_method_type}PostedDart");
+        let direct_address = format!("{method_name}Direct");
+        let posted_address = format!("{method_name}Posted");
+        let native_return_type = match m.return_type() {
+            Some(ret) => DartCodeOracle::native_type_label(Some(ret), type_helper.get_ci()),
+            None => quote!(Void),
+        };
+        let tokens = quote! {
+            typedef $posted_type = Void Function(
+                Uint64, $(for arg in &m.arguments() => $(DartCodeOracle::native_type_label(Some(&arg.as_type()), type_helper.get_ci())),)
+                Pointer<$(&native_return_type)>, Pointer<RustCallStatus>, Uint64);
+            typedef $posted_dart_type = void Function(
+                int, $(for arg in &m.arguments() => $(DartCodeOracle::native_dart_type_label(Some(&arg.as_type()), type_helper.get_ci())),)
+                Pointer<$(&native_return_type)>, Pointer<RustCallStatus>, int);
+
+            $trampoline
+
+            void $posted_name(int uniffiHandle, $(for param in &param_types => $param,) $out_return_type outReturn, Pointer<RustCallStatus> callStatus, int rendezvous) {
+                // The calling thread may have given up on the call, see `uniffi_dart_rendezvous_wait`
+                if (!uniffi_dart_rendezvous_claim(rendezvous)) {
+                    return;
+                }
+                try {
+                    $callback_method_name(uniffiHandle, $(for name in &param_names => $name,) outReturn, callStatus);
+                } finally {
+                    uniffi_dart_rendezvous_signal(rendezvous, 0);
+                }
+            }
+
+            void $blocking_name(int uniffiHandle, $(for param in &param_types => $param,) $out_return_type outReturn, Pointer<RustCallStatus> callStatus) {
+                if (uniffi_dart_isolate_entered($dispatch_cls.uniffiIsolate)) {
+                    Pointer<NativeFunction<$ffi_method_type>>.fromAddress($dispatch_cls.$(&direct_address))
+                        .asFunction<$dart_method_type>()(uniffiHandle, $(for name in &param_names => $name,) outReturn, callStatus);
+                    return;
+                }
+                final rendezvous = uniffi_dart_rendezvous_new($dispatch_cls.uniffiIsolate);
+                Pointer<NativeFunction<$posted_type>>.fromAddress($dispatch_cls.$(&posted_address))
+                    .asFunction<$posted_dart_type>()(uniffiHandle, $(for name in &param_names => $name,) outReturn, callStatus, rendezvous);
+                uniffi_dart_rendezvous_wait(rendezvous, callStatus);
+            }
+
+            final Pointer<NativeFunction<$ffi_method_type>> $(callback_method_name)Pointer = () {
+                $dispatch_cls.$(&direct_address) = Pointer.fromFunction<$ffi_method_type>($callback_method_name).address;
+                $dispatch_cls.$(&posted_address) = $(listener_callable(posted_type, posted_name)).nativeFunction.address;
+                return (NativeCallable<$ffi_method_type>.isolateGroupBound($blocking_name)..keepIsolateAlive = false).nativeFunction;
+            }();
+        };
+        shared_addresses.push(direct_address);
+        shared_addresses.push(posted_address);
+        tokens
     }).collect();
 
     // Free callback
@@ -293,9 +435,7 @@ pub fn generate_callback_functions(
     let clone_callback_pointer = &format!("{}ClonePointer", DartCodeOracle::fn_name(callback_name));
     let clone_callback_type = &format!("UniffiCallbackInterface{callback_name}Clone");
 
-    quote! {
-        $(functions)
-
+    let free_callback = quote! {
         void $free_callback_fn(int handle) {
             try {
                 FfiConverterCallbackInterface$cls_name._handleMap.remove(handle);
@@ -303,10 +443,8 @@ pub fn generate_callback_functions(
                 // Optionally log error, but do not return anything.
             }
         }
-
-        final Pointer<NativeFunction<$free_callback_type>> $free_callback_pointer =
-            Pointer.fromFunction<$free_callback_type>($free_callback_fn);
-
+    };
+    let clone_callback = quote! {
         int $clone_callback_fn(int handle) {
             try {
                 final obj = FfiConverterCallbackInterface$cls_name._handleMap.get(handle);
@@ -317,22 +455,276 @@ pub fn generate_callback_functions(
                 return 0;
             }
         }
+    };
+
+    if !thread_safe {
+        return quote! {
+            $(functions)
+
+            $free_callback
+
+            final Pointer<NativeFunction<$free_callback_type>> $free_callback_pointer =
+                Pointer.fromFunction<$free_callback_type>($free_callback_fn);
+
+            $clone_callback
+
+            final Pointer<NativeFunction<$clone_callback_type>> $clone_callback_pointer =
+                Pointer.fromFunction<$clone_callback_type>($clone_callback_fn, 0);
+        };
+    }
+
+    let clone_posted_fn = &format!("{}ClonePosted", DartCodeOracle::fn_name(callback_name));
+    let clone_blocking_fn = &format!("{}CloneBlocking", DartCodeOracle::fn_name(callback_name));
+    let clone_posted_type = &format!("UniffiCallbackInterface{callback_name}ClonePosted");
+
+    quote! {
+        // Where calls from other threads are sent. Those threads cannot see isolate state, so
+        // the addresses live in fields shared by the whole isolate group.
+        final class $dispatch_cls {
+            // The isolate owning the implementations, set once it claimed them in the vtable
+            // initialization. See `uniffi_dart_callback_owner_claim`.
+            @pragma("vm:shared")
+            static int uniffiIsolate = 0;
+            @pragma("vm:shared")
+            static int uniffiClone = 0;
+            @pragma("vm:shared")
+            static int uniffiClonePosted = 0;
+            $(for address in &shared_addresses {
+                @pragma("vm:shared")
+                static int $address = 0;
+            })
+        }
+
+        typedef $clone_posted_type = Void Function(Uint64, Uint64);
+        typedef $(clone_posted_type)Dart = void Function(int, int);
+
+        $(functions)
+
+        $free_callback
+
+        $(listener_pointer(free_callback_type, free_callback_fn, &format!("{}Free", DartCodeOracle::fn_name(callback_name))))
+
+        $clone_callback
+
+        void $clone_posted_fn(int handle, int rendezvous) {
+            if (!uniffi_dart_rendezvous_claim(rendezvous)) {
+                return;
+            }
+            uniffi_dart_rendezvous_signal(rendezvous, $clone_callback_fn(handle));
+        }
+
+        // Rust uses the new handle straight away, so the clone has to be made before returning.
+        // If the isolate cannot answer, the handle is 0 and calls through it fail as stale.
+        int $clone_blocking_fn(int handle) {
+            if (uniffi_dart_isolate_entered($dispatch_cls.uniffiIsolate)) {
+                return Pointer<NativeFunction<$clone_callback_type>>.fromAddress($dispatch_cls.uniffiClone)
+                    .asFunction<UniffiCallbackInterface$(callback_name)CloneDart>()(handle);
+            }
+            final rendezvous = uniffi_dart_rendezvous_new($dispatch_cls.uniffiIsolate);
+            Pointer<NativeFunction<$clone_posted_type>>.fromAddress($dispatch_cls.uniffiClonePosted)
+                .asFunction<$(clone_posted_type)Dart>()(handle, rendezvous);
+            return uniffi_dart_rendezvous_wait(rendezvous, nullptr);
+        }
+
+        final Pointer<NativeFunction<$clone_callback_type>> $clone_callback_pointer = () {
+            $dispatch_cls.uniffiClone = Pointer.fromFunction<$clone_callback_type>($clone_callback_fn, 0).address;
+            $dispatch_cls.uniffiClonePosted = $(listener_callable(clone_posted_type, clone_posted_fn)).nativeFunction.address;
+            return (NativeCallable<$clone_callback_type>.isolateGroupBound($clone_blocking_fn, exceptionalReturn: 0)..keepIsolateAlive = false).nativeFunction;
+        }();
+    }
+}
+
+/// Debug tracking statements for the RustBuffers a callback method receives and hands back,
+/// empty unless `debug_tracking` is enabled. The first counts the buffers Rust passes as
+/// arguments, which lifting them frees again. The second gives up the buffer lowered into the
+/// return value or, on failure, into the error of `status`, both of which Rust takes.
+fn callback_buffer_tracking(
+    method: &Method,
+    status: dart::Tokens,
+    type_helper: &dyn TypeHelperRenderer,
+) -> (dart::Tokens, dart::Tokens) {
+    if !type_helper.get_config().debug_tracking() {
+        return (quote!(), quote!());
+    }
+    let is_buffer = |ty: &Type| matches!(FfiType::from(ty), FfiType::RustBuffer(_));
+    let received = method
+        .arguments()
+        .iter()
+        .filter(|arg| is_buffer(&arg.as_type()))
+        .count();
+    let returns_buffer = method.return_type().is_some_and(is_buffer);
+
+    let receive = if received > 0 {
+        quote!(_uniffiOutstandingRustBuffers += $received;)
+    } else {
+        quote!()
+    };
+    // Exactly one of the return value and the error is set
+    let hand_over = if returns_buffer {
+        quote!(_uniffiOutstandingRustBuffers--;)
+    } else {
+        quote! {
+            if ($(&status).code != CALL_SUCCESS) {
+                _uniffiOutstandingRustBuffers--;
+            }
+        }
+    };
+    (receive, hand_over)
+}
+
+/// A listener callable for `function`. The vtable lives for the whole program, so it must not
+/// keep the isolate alive on its own.
+fn listener_callable(native_type: &str, function: &str) -> dart::Tokens {
+    quote!((NativeCallable<$native_type>.listener($function)..keepIsolateAlive = false))
+}
 
-        final Pointer<NativeFunction<$clone_callback_type>> $clone_callback_pointer =
-            Pointer.fromFunction<$clone_callback_type>($clone_callback_fn, 0);
+fn listener_pointer(native_type: &str, function: &str, pointer_prefix: &str) -> dart::Tokens {
+    quote! {
+        final Pointer<NativeFunction<$native_type>> $(pointer_prefix)Pointer =
+            $(listener_callable(native_type, function)).nativeFunction;
     }
 }
 
+/// Async callback methods return immediately and report their outcome later by calling the
+/// completion function Rust handed us, following UniFFI's foreign-future protocol.
+fn generate_async_callback_function(
+    method: &Method,
+    type_helper: &dyn TypeHelperRenderer,
+    cls_name: &str,
+    callback_method_name: &str,
+    param_types: Vec<dart::Tokens>,
+    arg_lifts: Vec<dart::Tokens>,
+    arg_names: Vec<dart::Tokens>,
+) -> dart::Tokens {
+    let method_name = DartCodeOracle::fn_name(method.name());
+    let suffix = DartCodeOracle::foreign_future_suffix(method.return_type());
+    let complete_type = &format!("UniffiForeignFutureComplete{suffix}");
+    let result_type = &format!("UniffiForeignFutureResult{suffix}");
+
+    let call_dart_method = match method.return_type() {
+        Some(ret) => {
+            let value = DartCodeOracle::foreign_future_return_value(ret, quote!(value));
+            quote! {
+                final value = await obj.$(&method_name)($(for arg in &arg_names => $arg,));
+                result.ref.returnValue = $value;
+            }
+        }
+        None => quote! {
+            await obj.$(&method_name)($(for arg in &arg_names => $arg,));
+        },
+    };
+
+    let (receive_buffers, hand_over_buffers) =
+        callback_buffer_tracking(method, quote!(result.ref.callStatus), type_helper);
+
+    quote! {
+        void $callback_method_name(int uniffiHandle, $(for param in &param_types => $param,) Pointer<NativeFunction<$complete_type>> uniffiFutureCallback, int uniffiCallbackData, Pointer<UniffiForeignFutureDroppedCallbackStruct> uniffiOutDroppedCallback) {
+            final complete = uniffiFutureCallback.asFunction<$(complete_type)Dart>();
+            $receive_buffers
+            () async {
+                final result = calloc<$result_type>();
+                try {
+                    final obj = FfiConverterCallbackInterface$cls_name._handleMap.get(uniffiHandle);
+                    $(arg_lifts)
+                    $call_dart_method
+                    result.ref.callStatus.code = CALL_SUCCESS;
+                } $(DartCodeOracle::callback_error_catch(method.throws_type(), quote!(result.ref.callStatus))) catch (e) {
+                    result.ref.callStatus.code = CALL_UNEXPECTED_ERROR;
+                    result.ref.callStatus.errorBuf = FfiConverterString.lower(e.toString());
+                }
+                $hand_over_buffers
+                try {
+                    complete(uniffiCallbackData, result.ref);
+                } finally {
+                    calloc.free(result);
+                }
+            }();
+        }
+    }
+}
+
+/// Result structs and completion callbacks for every return type an async callback method
+/// can have. Rust picks the one matching the method's FFI return type.
+pub fn generate_foreign_future_definitions() -> dart::Tokens {
+    let result_kinds = [
+        ("U8", quote!(@Uint8() external int returnValue;)),
+        ("I8", quote!(@Int8() external int returnValue;)),
+        ("U16", quote!(@Uint16() external int returnValue;)),
+        ("I16", quote!(@Int16() external int returnValue;)),
+        ("U32", quote!(@Uint32() external int returnValue;)),
+        ("I32", quote!(@Int32() external int returnValue;)),
+        ("U64", quote!(@Uint64() external int returnValue;)),
+        ("I64", quote!(@Int64() external int returnValue;)),
+        ("F32", quote!(@Float() external double returnValue;)),
+        ("F64", quote!(@Double() external double returnValue;)),
+        ("RustBuffer", quote!(external RustBuffer returnValue;)),
+        // `()` is zero sized on the Rust side, so there is no return value field
+        ("Void", quote!()),
+    ];
+
+    let mut tokens = quote! {
+        typedef UniffiForeignFutureDroppedCallback = Void Function(Uint64);
+
+        final class UniffiForeignFutureDroppedCallbackStruct extends Struct {
+            @Uint64()
+            external int handle;
+
+            external Pointer<NativeFunction<UniffiForeignFutureDroppedCallback>> free;
+        }
+    };
+
+    for (suffix, return_field) in result_kinds {
+        let result_type = &format!("UniffiForeignFutureResult{suffix}");
+        let complete_type = &format!("UniffiForeignFutureComplete{suffix}");
+        tokens.append(quote! {
+            final class $result_type extends Struct {
+                $return_field
+                external RustCallStatus callStatus;
+            }
+
+            typedef $complete_type = Void Function(Uint64, $result_type);
+            typedef $(complete_type)Dart = void Function(int, $result_type);
+        });
+    }
+
+    tokens
+}
+
 pub fn generate_callback_interface_vtable_init_function(
     callback_name: &str,
     methods: &[&Method],
     ffi_module: &str,
+    type_helper: &dyn TypeHelperRenderer,
 ) -> dart::Tokens {
+    let cls_name = &DartCodeOracle::class_name(callback_name);
     let vtable_name = &format!("UniffiVTableCallbackInterface{callback_name}");
     let vtable_static_instance_name =
         format!("{}{}", DartCodeOracle::fn_name(callback_name), "VTable");
     let init_fn_name = &format!("init{callback_name}VTable");
     let snake_callback = callback_name.to_lowercase();
+    let init_callback_vtable =
+        &format!("uniffi_{ffi_module}_fn_init_callback_vtable_{snake_callback}");
+
+    // Rust keeps one vtable for the whole process, which thread-safe dispatch binds to the isolate
+    // initializing it. The address of the Rust function taking the vtable identifies the interface.
+    let claim_owner = if type_helper.get_config().callback_dispatch(callback_name)
+        == CallbackDispatch::ThreadSafe
+    {
+        let owner_error = format!(
+            "\"{cls_name} implementations are registered by another isolate already, thread-safe callback interfaces can only be implemented in one isolate\""
+        );
+        quote! {
+            final owner = uniffi_dart_callback_owner_claim(
+                Native.addressOf<NativeFunction<Void Function(Pointer<$vtable_name>)>>($init_callback_vtable).address,
+                _uniffiIsolateId);
+            if (owner != _uniffiIsolateId) {
+                throw StateError($owner_error);
+            }
+            _UniffiCallbackInterface$(cls_name)Dispatch.uniffiIsolate = owner;
+        }
+    } else {
+        quote!()
+    };
 
     quote! {
         late final Pointer<$vtable_name> $(&vtable_static_instance_name);
@@ -343,6 +735,7 @@ pub fn generate_callback_interface_vtable_init_function(
                 return;
             }
 
+            $claim_owner
             $(&vtable_static_instance_name) = calloc<$vtable_name>();
             $(&vtable_static_instance_name).ref.uniffiFree = $(format!("{}FreePointer", DartCodeOracle::fn_name(callback_name)));
             $(&vtable_static_instance_name).ref.uniffiClone = $(format!("{}ClonePointer", DartCodeOracle::fn_name(callback_name)));
@@ -351,7 +744,7 @@ pub fn generate_callback_interface_vtable_init_function(
             })
 
             rustCall((status) {
-                uniffi_$(ffi_module)_fn_init_callback_vtable_$(snake_callback)(
+                $init_callback_vtable(
                     $(vtable_static_instance_name),
                 );
                 checkCallStatus(NullRustCallStatusErrorHandler(), status);
@@ -99,7 +99,7 @@ macro_rules! impl_renderable_for_compound {
                         class $cl_name {
 
                             static $type_label lift( RustBuffer buf) {
-                                return $cl_name.read(buf.asUint8List()).value;
+                                return liftFromRustBuffer(buf, $cl_name.read);
                             }
 
                             static LiftRetVal<$type_label> read( Uint8List buf) {
@@ -123,17 +123,9 @@ macro_rules! impl_renderable_for_compound {
                                     return toRustBuffer(Uint8List.fromList([0]));
                                 }
 
-                                final length = $cl_name.allocationSize(value);
-
-                                final Pointer<Uint8> frameData = calloc<Uint8>(length); // Allocate a pointer large enough.
-                                final buf = frameData.asTypedList(length); // Create a list that uses our pointer to copy in the data.
-
+                                final buf = Uint8List($cl_name.allocationSize(value));
                                 $cl_name.write(value, buf);
-
-                                final bytes = calloc<ForeignBytes>();
-                                bytes.ref.len = length;
-                                bytes.ref.data = frameData;
-                                return RustBuffer.fromBytes(bytes.ref);
+                                return toRustBuffer(buf);
                             }
 
                             static int write( $type_label value, Uint8List buf) {
@@ -198,7 +190,7 @@ macro_rules! impl_renderable_for_compound {
                         class $cl_name {
 
                             static $type_label lift( RustBuffer buf) {
-                                return $cl_name.read(buf.asUint8List()).value;
+                                return liftFromRustBuffer(buf, $cl_name.read);
                             }
 
                             static LiftRetVal<$type_label> read( Uint8List buf) {
@@ -310,7 +302,7 @@ impl Renderable for MapCodeType {
         quote! {
             class $cl_name {
                 static Map<$key_type_label, $val_type_label> lift(RustBuffer buf) {
-                    return $cl_name.read(buf.asUint8List()).value;
+                    return liftFromRustBuffer(buf, $cl_name.read);
                 }
 
                 static LiftRetVal<Map<$key_type_label, $val_type_label>> read(Uint8List buf) {
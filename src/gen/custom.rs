@@ -1,5 +1,5 @@
 use super::oracle::{AsCodeType, DartCodeOracle};
-use super::render::{Renderable, TypeHelperRenderer};
+use super::render::{AsRenderable, Renderable, TypeHelperRenderer};
 use super::CodeType;
 use genco::prelude::*;
 use uniffi_bindgen::interface::AsType;
@@ -44,12 +44,68 @@ impl Renderable for CustomCodeType {
 
         let ffi_converter_name = &self.ffi_converter_name();
         let type_name = &self.type_label();
-        let builtin_ffi_converter_name = &(*self.builtin).as_codetype().ffi_converter_name();
+        let builtin_codetype = (*self.builtin).as_codetype();
+        let builtin_ffi_converter_name = &builtin_codetype.ffi_converter_name();
         let builtin_name = DartCodeOracle::dart_type_label(Some(&*self.builtin));
 
+        // Custom converters delegate to the builtin one, so make sure it is rendered too
+        let builtin_helper =
+            if type_helper.include_once_check(&builtin_codetype.canonical_name(), &self.builtin) {
+                quote!()
+            } else {
+                self.builtin.as_renderable().render_type_helper(type_helper)
+            };
+
+        let Some(custom) = type_helper.get_config().custom_type(&self.name) else {
+            return quote! {
+                typedef $(type_name) = $(builtin_name);
+                typedef $(ffi_converter_name) = $(builtin_ffi_converter_name);
+
+                $builtin_helper
+            };
+        };
+
+        // Without an explicit `type_name` the custom type keeps the builtin Dart type
+        let dart_type_name = match custom.type_name() {
+            Some(name) => quote!($name),
+            None => builtin_name,
+        };
+        let type_alias = if dart_type_name.to_string().ok().as_deref() == Some(type_name.as_str()) {
+            quote!()
+        } else {
+            quote!(typedef $(type_name) = $(dart_type_name);)
+        };
+        let builtin_ffi_type =
+            DartCodeOracle::native_dart_type_label(Some(&*self.builtin), type_helper.get_ci());
+
         quote! {
-            typedef $(type_name) = $(builtin_name);
-            typedef $(ffi_converter_name) = $(builtin_ffi_converter_name);
+            $type_alias
+
+            class $ffi_converter_name {
+                static $type_name lift($(&builtin_ffi_type) value) {
+                    final builtinValue = $builtin_ffi_converter_name.lift(value);
+                    return $(custom.lift_expr("builtinValue"));
+                }
+
+                static $(&builtin_ffi_type) lower($type_name value) {
+                    return $builtin_ffi_converter_name.lower($(custom.lower_expr("value")));
+                }
+
+                static LiftRetVal<$type_name> read(Uint8List buf) {
+                    final builtinValue = $builtin_ffi_converter_name.read(buf);
+                    return LiftRetVal($(custom.lift_expr("builtinValue.value")), builtinValue.bytesRead);
+                }
+
+                static int allocationSize($type_name value) {
+                    return $builtin_ffi_converter_name.allocationSize($(custom.lower_expr("value")));
+                }
+
+                static int write($type_name value, Uint8List buf) {
+                    return $builtin_ffi_converter_name.write($(custom.lower_expr("value")), buf);
+                }
+            }
+
+            $builtin_helper
         }
     }
 }
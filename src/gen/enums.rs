@@ -3,7 +3,9 @@ use genco::prelude::*;
 use heck::ToLowerCamelCase;
 use uniffi_bindgen::interface::{AsType, Enum, Field, Type};
 use uniffi_bindgen::pipeline::general::nodes::Literal;
+use uniffi_meta::LiteralMetadata;
 
+use super::objects::generate_uniffi_trait_methods;
 use super::oracle::{AsCodeType, DartCodeOracle};
 use super::render::{AsRenderable, Renderable, TypeHelperRenderer};
 
@@ -60,37 +62,91 @@ impl Renderable for EnumCodeType {
 pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
     let dart_cls_name = &DartCodeOracle::class_name(obj.name());
     let ffi_converter_name = &obj.as_codetype().ffi_converter_name();
-    if obj.is_flat() {
-        let is_error_enum = type_helper.get_ci().is_name_used_as_error(obj.name());
-        let implements_exception = if is_error_enum {
-            quote!( implements Exception)
-        } else {
-            quote!()
-        };
-
-        // For flat error enums, generate an error handler
-        let error_handler_class = if is_error_enum {
-            let error_handler_name = format!("{dart_cls_name}ErrorHandler");
-            let instance_name = dart_cls_name.to_lower_camel_case();
-            quote! {
-                class $(&error_handler_name) extends UniffiRustCallStatusErrorHandler {
-                    @override
-                    Exception lift(RustBuffer errorBuf) {
-                        return $ffi_converter_name.lift(errorBuf);
-                    }
-                }
-
-                final $(&error_handler_name) $(instance_name)ErrorHandler = $(&error_handler_name)();
+    let trait_methods = obj.uniffi_trait_methods();
+    let is_error_enum = type_helper.get_ci().is_name_used_as_error(obj.name());
+    let mut implements = vec![];
+    if trait_methods.ord_cmp.is_some() {
+        implements.push(quote!(Comparable<$dart_cls_name>));
+    }
+    let class_clause = quote!(
+        $(if is_error_enum { extends UniffiException })
+        $(if !implements.is_empty() { implements $(for i in implements join (, ) => $i) })
+    );
+    let receiver = quote!($ffi_converter_name.lower(this));
+    let non_exhaustive = obj.is_non_exhaustive();
+    let lower_unknown_error = quote!(throw UniffiInternalError(UniffiInternalError.unexpectedEnumCase, "Unknown variants cannot be passed back to Rust"));
+
+    if obj.is_flat() && !is_error_enum {
+        let unknown_variant = unknown_name(
+            "unknown",
+            obj.variants()
+                .iter()
+                .map(|v| DartCodeOracle::enum_variant_name(v.name())),
+        );
+        // Dart ints are 64 bits wide, so `u64` discriminants past `i64::MAX` keep their bits
+        let discriminants = (0..obj.variants().len()).map(|i| {
+            match obj
+                .variant_discr(i)
+                .expect("flat enum discriminants are integers")
+            {
+                LiteralMetadata::UInt(v, ..) => v as i64,
+                LiteralMetadata::Int(v, ..) => v,
+                lit => unreachable!("invalid enum discriminant {lit:?}"),
             }
+        });
+        let (from_value_doc, from_value_fallback) = if non_exhaustive {
+            (
+                "The variant whose Rust discriminant is `value`, or `unknown` if there is none.",
+                quote!($dart_cls_name.$(&unknown_variant)),
+            )
         } else {
-            quote!()
+            (
+                "The variant whose Rust discriminant is `value`.",
+                quote!(throw ArgumentError.value(value, "value", $(format!("\"Not a {dart_cls_name} discriminant\"")))),
+            )
         };
 
+        // Dart enums cannot override `==` or `hashCode`, they compare by identity which is what
+        // a fieldless Rust enum does as well
+        let mut flat_trait_methods = obj.uniffi_trait_methods();
+        flat_trait_methods.eq_eq = None;
+        flat_trait_methods.hash_hash = None;
+        let uniffi_trait_methods = generate_uniffi_trait_methods(
+            dart_cls_name,
+            &flat_trait_methods,
+            &receiver,
+            type_helper,
+        );
+
         quote! {
-            enum $dart_cls_name $implements_exception {
-                $(for variant in obj.variants() =>
-                $(DartCodeOracle::enum_variant_name(variant.name())),)
+            $(DartCodeOracle::docstring(obj.docstring()))
+            enum $dart_cls_name $class_clause {
+                $(for (variant, discriminant) in obj.variants().iter().zip(discriminants) {
+                    $(DartCodeOracle::docstring(variant.docstring()))
+                    $(DartCodeOracle::enum_variant_name(variant.name()))($discriminant),
+                })
+                $(if non_exhaustive {
+                    $(DartCodeOracle::docstring(Some("A variant added to the Rust enum after these bindings were generated. It has no discriminant of its own and reports -1.")))
+                    $(&unknown_variant)(-1),
+                })
                 ;
+
+                const $dart_cls_name(this.value);
+
+                $(DartCodeOracle::docstring(Some("The discriminant of the Rust variant.")))
+                final int value;
+
+                $(DartCodeOracle::docstring(Some(from_value_doc)))
+                static $dart_cls_name fromValue(int value) {
+                    return values.firstWhere(
+                        (variant) => $(if non_exhaustive { variant != $dart_cls_name.$(&unknown_variant) && })variant.value == value,
+                        orElse: () => $from_value_fallback,
+                    );
+                }
+
+                $uniffi_trait_methods
+
+                $(type_helper.get_config().value_type_methods().generate_members(&obj.as_type(), type_helper))
             }
 
             class $ffi_converter_name {
@@ -105,15 +161,24 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
                             );
                         )
                         default:
-                            throw UniffiInternalError(UniffiInternalError.unexpectedEnumCase, "Unable to determine enum variant");
+                            $(if non_exhaustive {
+                                return LiftRetVal($dart_cls_name.$(&unknown_variant), 4);
+                            } else {
+                                throw UniffiInternalError(UniffiInternalError.unexpectedEnumCase, "Unable to determine enum variant");
+                            })
                     }
                 }
 
                 static $dart_cls_name lift( RustBuffer buffer) {
-                    return $ffi_converter_name.read(buffer.asUint8List()).value;
+                    return liftFromRustBuffer(buffer, $ffi_converter_name.read);
                 }
 
                 static RustBuffer lower( $dart_cls_name input) {
+                    $(if non_exhaustive {
+                        if (input == $dart_cls_name.$(&unknown_variant)) {
+                            $(&lower_unknown_error);
+                        }
+                    })
                     return toRustBuffer(createUint8ListFromInt(input.index + 1));
                 }
 
@@ -122,17 +187,30 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
                 }
 
                 static int write( $dart_cls_name value, Uint8List buf) {
+                    $(if non_exhaustive {
+                        if (value == $dart_cls_name.$(&unknown_variant)) {
+                            $(&lower_unknown_error);
+                        }
+                    })
                     buf.buffer
                         .asByteData(buf.offsetInBytes)
                         .setInt32(0, value.index + 1);
                     return 4;
                 }
             }
-
-            $error_handler_class
         }
     } else {
+        // Flat errors cross from Rust as the variant index and the `Display` message, and go back
+        // as the index alone
+        let flat_error = obj.is_flat();
+        if flat_error {
+            type_helper
+                .include_once_check(&Type::String.as_codetype().canonical_name(), &Type::String);
+        }
         let mut variants = vec![];
+        let mut read_cases = vec![];
+        let mut write_cases = vec![];
+        let mut allocation_cases = vec![];
 
         // helper functions to get the sanitized field name and type strings
         fn field_name(field: &Field, field_num: usize) -> String {
@@ -158,17 +236,14 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
                 .ffi_converter_name()
                 .replace("Error", "Exception")
         }
-        fn is_flat_enum(field: &Field, type_helper: &dyn TypeHelperRenderer) -> bool {
-            if let Type::Enum { name, .. } = &field.as_type() {
-                if let Some(enum_def) = type_helper.get_enum(name) {
-                    return enum_def.is_flat();
-                }
-            }
-            false
-        }
 
         for (index, variant_obj) in obj.variants().iter().enumerate() {
-            for f in variant_obj.fields() {
+            let fields = if flat_error {
+                &[]
+            } else {
+                variant_obj.fields()
+            };
+            for f in fields {
                 type_helper.include_once_check(&f.as_codetype().canonical_name(), &f.as_type());
             }
             let variant_dart_cls_name = &format!(
@@ -176,16 +251,16 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
                 DartCodeOracle::class_name(variant_obj.name()),
                 dart_cls_name
             );
+            let is_named = fields.len() > 1;
 
             // Prepare constructor parameters
-            let constructor_params = variant_obj
-                .fields()
+            let constructor_params = fields
                 .iter()
                 .enumerate()
                 .map(|(i, field)| {
                     let param_name = field_name(field, i);
                     let param_type = field_type(field, type_helper);
-                    if variant_obj.fields().len() > 1 {
+                    if is_named {
                         quote!(required $param_type this.$param_name)
                     } else {
                         quote!($param_type this.$param_name)
@@ -193,62 +268,110 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
                 })
                 .collect::<Vec<_>>();
 
-            let constructor_param_list = if variant_obj.fields().len() > 1 {
-                quote!({ $( for p in constructor_params => $p, ) })
+            let constructor_param_list = if flat_error {
+                quote!([super.message = "", super.rustFunctionName])
+            } else if is_named {
+                quote!({ $( for p in constructor_params => $p, ) $(if is_error_enum { super.rustFunctionName })})
+            } else if is_error_enum {
+                quote!($( for p in constructor_params => $p, ) {super.rustFunctionName})
             } else {
                 quote!($( for p in constructor_params => $p, ))
             };
 
-            // Pre-process field reading code
-            let field_read_code: Vec<dart::Tokens> = variant_obj.fields().iter().enumerate().map(|(i, field)| {
-                if is_flat_enum(field, type_helper) {
-                    // Handle flat enums specially - they serialize as int32 (4 bytes)
-                    quote!(
-                        final $(field_name(field, i))_int = buf.buffer.asByteData(new_offset).getInt32(0);
-                        final $(field_name(field, i)) = $(field_ffi_converter_name(field)).lift(toRustBuffer(createUint8ListFromInt($(field_name(field, i))_int)));
-                        new_offset += 4;
-                    )
+            let constructor_args = fields.iter().enumerate().map(|(i, field)| {
+                let name = field_name(field, i);
+                if is_named {
+                    quote!($(&name): $(&name))
                 } else {
-                    quote!(
+                    quote!($name)
+                }
+            });
+            let function_name_arg = if flat_error {
+                quote!(rustFunctionName)
+            } else if is_error_enum {
+                quote!(rustFunctionName: rustFunctionName)
+            } else {
+                quote!()
+            };
+            read_cases.push(quote! {
+                case $(index + 1): {
+                    $(for (i, field) in fields.iter().enumerate() =>
                         final $(field_name(field, i))_lifted = $(field_ffi_converter_name(field)).read(Uint8List.view(buf.buffer, new_offset));
                         final $(field_name(field, i)) = $(field_name(field, i))_lifted.value;
                         new_offset += $(field_name(field, i))_lifted.bytesRead;
                     )
+                    $(if flat_error {
+                        final message_lifted = FfiConverterString.read(Uint8List.view(buf.buffer, new_offset));
+                        new_offset += message_lifted.bytesRead;
+                        return LiftRetVal($variant_dart_cls_name(message_lifted.value, $(&function_name_arg)), new_offset - buf.offsetInBytes);
+                    } else {
+                        return LiftRetVal($variant_dart_cls_name($(for a in constructor_args => $a,) $(&function_name_arg)), new_offset - buf.offsetInBytes);
+                    })
                 }
-            }).collect();
+            });
 
-            // Pre-process allocation size calculation
-            let allocation_parts: Vec<dart::Tokens> = variant_obj.fields().iter().enumerate().map(|(i, field)| {
-                if is_flat_enum(field, type_helper) {
-                    quote!(4 + ) // Flat enums are 4 bytes (int32)
-                } else {
-                    quote!($(field_ffi_converter_name(field)).allocationSize($(field_name(field, i))) + )
-                }
-            }).collect();
-
-            // Pre-process field write code
-            let field_write_code: Vec<dart::Tokens> = variant_obj.fields().iter().enumerate().map(|(i, field)| {
-                if is_flat_enum(field, type_helper) {
-                    // Handle flat enums specially - lower to RustBuffer and extract int32
-                    quote!(
-                        final $(field_name(field, i))_buffer = $(field_ffi_converter_name(field)).lower($(field_name(field, i)));
-                        final $(field_name(field, i))_int = $(field_name(field, i))_buffer.asUint8List().buffer.asByteData().getInt32(0);
-                        buf.buffer.asByteData(new_offset).setInt32(0, $(field_name(field, i))_int);
-                        new_offset += 4;
-                    )
-                } else {
-                    quote!(
-                        new_offset += $(field_ffi_converter_name(field)).write($(field_name(field, i)), Uint8List.view(buf.buffer, new_offset));
+            // Fieldless variants only need a type test, the others bind the value to reach it
+            let pattern = if fields.is_empty() {
+                quote!($variant_dart_cls_name())
+            } else {
+                quote!($variant_dart_cls_name variant)
+            };
+            write_cases.push(quote! {
+                case $(&pattern): {
+                    buf.buffer.asByteData(buf.offsetInBytes).setInt32(0, $(index + 1));
+                    int new_offset = buf.offsetInBytes + 4;
+                    $(for (i, field) in fields.iter().enumerate() =>
+                        new_offset += $(field_ffi_converter_name(field)).write(variant.$(field_name(field, i)), Uint8List.view(buf.buffer, new_offset));
                     )
+                    return new_offset - buf.offsetInBytes;
                 }
-            }).collect();
+            });
+            allocation_cases.push(quote! {
+                case $(&pattern):
+                    return $(for (i, field) in fields.iter().enumerate() => $(field_ffi_converter_name(field)).allocationSize(variant.$(field_name(field, i))) + ) 4;
+            });
+
+            let mut field_equality = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let name = field_name(field, i);
+                    if DartCodeOracle::needs_deep_equality(&field.as_type()) {
+                        quote!(uniffiDeepEquals(this.$(&name), other.$(&name)))
+                    } else {
+                        quote!(this.$(&name) == other.$(&name))
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut field_hashes = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let name = field_name(field, i);
+                    if DartCodeOracle::needs_deep_equality(&field.as_type()) {
+                        quote!(uniffiDeepHash($name))
+                    } else {
+                        quote!($name)
+                    }
+                })
+                .collect::<Vec<_>>();
+            if flat_error {
+                field_equality.push(quote!(message == other.message));
+                field_hashes.push(quote!(message));
+            }
 
-            // Generate simple toString() method for error enum variants
+            // Generate simple toString() method for error enum variants, unless Rust provides one
             let to_string_method: dart::Tokens =
-                if type_helper.get_ci().is_name_used_as_error(obj.name()) {
-                    if variant_obj.has_fields() {
-                        let field_interpolations = variant_obj
-                            .fields()
+                if trait_methods.display_fmt.is_none() && is_error_enum {
+                    if flat_error {
+                        quote!(
+                            @override
+                            String toString() {
+                                return $(format!("\"{variant_dart_cls_name}($message)\""));
+                            }
+                        )
+                    } else if variant_obj.has_fields() {
+                        let field_interpolations = fields
                             .iter()
                             .enumerate()
                             .map(|(i, field)| format!("${}", field_name(field, i)))
@@ -274,58 +397,129 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
                     quote!()
                 };
 
+            // Exported `Eq` and `Hash` live on the sealed base, variants must not shadow them
             variants.push(quote!{
-                class $variant_dart_cls_name extends $dart_cls_name {
-                    $(for (i, field) in variant_obj.fields().iter().enumerate() => final $(field_type(field, type_helper)) $(field_name(field, i));  )
+                $(DartCodeOracle::docstring(variant_obj.docstring()))
+                final class $variant_dart_cls_name extends $dart_cls_name {
+                    $(for (i, field) in fields.iter().enumerate() {
+                        $(DartCodeOracle::docstring(field.docstring()))
+                        final $(field_type(field, type_helper)) $(field_name(field, i));
+                    })
 
-                    // Add the public const constructor
                     $variant_dart_cls_name($constructor_param_list);
 
-                    // Keep the private constructor used by `read`
-                    $variant_dart_cls_name._($(for (i, field) in variant_obj.fields().iter().enumerate() => $(field_type(field, type_helper)) this.$(field_name(field, i)), ));
-
-                    static LiftRetVal<$variant_dart_cls_name> read( Uint8List buf) {
-                        int new_offset = buf.offsetInBytes;
-
-                        $(for code in &field_read_code => $code)
-                        return LiftRetVal($variant_dart_cls_name._(
-                            $(for (i, field) in variant_obj.fields().iter().enumerate() => $(field_name(field, i)),)
-                        ), new_offset);
-                    }
-
-                    @override
-                    RustBuffer lower() {
-                        final buf = Uint8List(allocationSize());
-                        write(buf);
-                        return toRustBuffer(buf);
-                    }
-
-                    @override
-                    int allocationSize() {
-                        return $(for part in &allocation_parts => $part) 4;
-                    }
-
-                    @override
-                    int write( Uint8List buf) {
-                        buf.buffer.asByteData(buf.offsetInBytes).setInt32(0, $(index + 1)); // write index into first position;
-                        int new_offset = buf.offsetInBytes + 4;
+                    $(if trait_methods.eq_eq.is_none() {
+                        @override
+                        bool operator ==(Object other) {
+                            return identical(this, other) ||
+                                (other is $variant_dart_cls_name $(for eq in field_equality => && $eq));
+                        }
+                    })
 
-                        $(for code in &field_write_code => $code)
-
-                        return new_offset;
-                    }
+                    $(if trait_methods.hash_hash.is_none() {
+                        @override
+                        int get hashCode => Object.hashAll([$(index + 1), $(for h in field_hashes => $h, )]);
+                    })
 
                     $to_string_method
                 }
             });
         }
 
-        let is_error_enum = type_helper.get_ci().is_name_used_as_error(obj.name());
-        let implements_exception = if is_error_enum {
-            quote!( implements Exception)
+        let uniffi_trait_methods =
+            generate_uniffi_trait_methods(dart_cls_name, &trait_methods, &receiver, type_helper);
+
+        // The payload of a variant this library doesn't know about can't be decoded, so only the
+        // discriminant is kept. Flat errors only carry their message, anything else has a payload
+        // of unknown size that can only be skipped when it runs to the end of the buffer.
+        let mut lift_reader = quote!($ffi_converter_name.read);
+        // Errors remember the Rust function that raised them
+        let function_name_param = if is_error_enum {
+            quote!(, [String? rustFunctionName])
         } else {
             quote!()
         };
+        let mut top_level_reader = quote!();
+        let unknown_read_case = if non_exhaustive {
+            let unknown_cls_name = &format!(
+                "{}{dart_cls_name}",
+                unknown_name(
+                    "Unknown",
+                    obj.variants()
+                        .iter()
+                        .map(|v| DartCodeOracle::class_name(v.name())),
+                )
+            );
+            write_cases.push(quote!(case $unknown_cls_name(): $(&lower_unknown_error);));
+            allocation_cases.push(quote!(case $unknown_cls_name(): $(&lower_unknown_error);));
+            let (message_param, message_equality, message_hash, to_string) = if flat_error {
+                (
+                    quote!(, [super.message = "", super.rustFunctionName]),
+                    quote!(&&message == other.message),
+                    quote!(Object.hash(discriminant, message)),
+                    format!("\"{unknown_cls_name}($discriminant, $message)\""),
+                )
+            } else {
+                (
+                    quote!($(if is_error_enum {, {super.rustFunctionName}})),
+                    quote!(),
+                    quote!(discriminant.hashCode),
+                    format!("\"{unknown_cls_name}($discriminant)\""),
+                )
+            };
+            variants.push(quote! {
+                $(DartCodeOracle::docstring(Some("A variant added to the Rust enum after these bindings were generated.")))
+                final class $unknown_cls_name extends $dart_cls_name {
+                    final int discriminant;
+
+                    $unknown_cls_name(this.discriminant$message_param);
+
+                    $(if trait_methods.eq_eq.is_none() {
+                        @override
+                        bool operator ==(Object other) {
+                            return identical(this, other) ||
+                                (other is $unknown_cls_name && discriminant == other.discriminant$message_equality);
+                        }
+                    })
+
+                    $(if trait_methods.hash_hash.is_none() {
+                        @override
+                        int get hashCode => $message_hash;
+                    })
+
+                    $(if trait_methods.display_fmt.is_none() {
+                        @override
+                        String toString() {
+                            return $(&to_string);
+                        }
+                    })
+                }
+            });
+            if flat_error {
+                quote! {
+                    final message = FfiConverterString.read(Uint8List.view(buf.buffer, new_offset));
+                    return LiftRetVal($unknown_cls_name(index, message.value, rustFunctionName), 4 + message.bytesRead);
+                }
+            } else {
+                let nested_error = format!(
+                    "\"Unknown {} variant $index inside another value\"",
+                    obj.name()
+                );
+                lift_reader = quote!(_readOrUnknown);
+                top_level_reader = quote! {
+                    static LiftRetVal<$dart_cls_name> _readOrUnknown(Uint8List buf$(&function_name_param)) {
+                        final index = buf.buffer.asByteData(buf.offsetInBytes).getInt32(0);
+                        if (index >= 1 && index <= $(obj.variants().len())) {
+                            return read(buf$(if is_error_enum {, rustFunctionName}));
+                        }
+                        return LiftRetVal($unknown_cls_name(index$(if is_error_enum {, rustFunctionName: rustFunctionName})), buf.lengthInBytes);
+                    }
+                };
+                quote!(throw UniffiInternalError(UniffiInternalError.unexpectedEnumCase, $nested_error);)
+            }
+        } else {
+            quote!(throw UniffiInternalError(UniffiInternalError.unexpectedEnumCase, "Unable to determine enum variant");)
+        };
 
         // For error enums, also generate an error handler
         let error_handler_class = if is_error_enum {
@@ -334,8 +528,8 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
             quote! {
                 class $(&error_handler_name) extends UniffiRustCallStatusErrorHandler {
                     @override
-                    Exception lift(RustBuffer errorBuf) {
-                        return $ffi_converter_name.lift(errorBuf);
+                    Exception lift(RustBuffer errorBuf, [String? functionName]) {
+                        return $ffi_converter_name.lift(errorBuf, functionName);
                     }
                 }
 
@@ -346,39 +540,76 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
         };
 
         quote! {
-            abstract class $dart_cls_name $implements_exception {
-                RustBuffer lower();
-                int allocationSize();
-                int write( Uint8List buf);
+            $(DartCodeOracle::docstring(obj.docstring()))
+            sealed class $dart_cls_name $class_clause {
+                $(if flat_error {
+                    $(DartCodeOracle::docstring(Some("The `Display` message of the Rust error, empty when raised from Dart.")))
+                    final String message;
+
+                    $dart_cls_name(this.message, [String? rustFunctionName]) : super(rustFunctionName: rustFunctionName);
+                })
+
+                $(if is_error_enum && !flat_error {
+                    $dart_cls_name({super.rustFunctionName});
+                })
+
+                $(if is_error_enum {
+                    @override
+                    String get rustTypeName => $(format!("\"{}\"", obj.name()));
+                })
+
+                $(if flat_error {
+                    @override
+                    String? get rustMessage => message.isEmpty ? null : message;
+                })
+
+                $(if is_error_enum && !flat_error && trait_methods.display_fmt.is_some() {
+                    @override
+                    String? get rustMessage => toString();
+                })
+
+                $uniffi_trait_methods
+
+                $(type_helper.get_config().value_type_methods().generate_members(&obj.as_type(), type_helper))
             }
 
             class $ffi_converter_name {
-                static $dart_cls_name lift( RustBuffer buffer) {
-                    return $ffi_converter_name.read(buffer.asUint8List()).value;
+                static $dart_cls_name lift( RustBuffer buffer$(&function_name_param)) {
+                    return liftFromRustBuffer(buffer, $(if is_error_enum {
+                        (buf) => $lift_reader(buf, rustFunctionName)
+                    } else {
+                        $lift_reader
+                    }));
                 }
 
-                static LiftRetVal<$dart_cls_name> read( Uint8List buf) {
+                $top_level_reader
+
+                static LiftRetVal<$dart_cls_name> read( Uint8List buf$(&function_name_param)) {
                     final index = buf.buffer.asByteData(buf.offsetInBytes).getInt32(0);
-                    final subview = Uint8List.view(buf.buffer, buf.offsetInBytes + 4);
+                    int new_offset = buf.offsetInBytes + 4;
                     switch(index) {
-                        $(for (index, variant) in obj.variants().iter().enumerate() =>
-                        case $(index + 1):
-                            return $(format!("{}{}", DartCodeOracle::class_name(variant.name()), dart_cls_name)).read(subview);
-                        )
-                        default:  throw UniffiInternalError(UniffiInternalError.unexpectedEnumCase, "Unable to determine enum variant");
+                        $(for case in read_cases => $case)
+                        default:
+                            $unknown_read_case
                     }
                 }
 
                 static RustBuffer lower( $dart_cls_name value) {
-                    return value.lower();
+                    final buf = Uint8List(allocationSize(value));
+                    write(value, buf);
+                    return toRustBuffer(buf);
                 }
 
                 static int allocationSize($dart_cls_name value) {
-                    return value.allocationSize();
+                    switch (value) {
+                        $(for case in allocation_cases => $case)
+                    }
                 }
 
                 static int write( $dart_cls_name value, Uint8List buf) {
-                    return value.write(buf);
+                    switch (value) {
+                        $(for case in write_cases => $case)
+                    }
                 }
             }
 
@@ -388,3 +619,14 @@ pub fn generate_enum(obj: &Enum, type_helper: &dyn TypeHelperRenderer) -> dart::
         }
     }
 }
+
+/// Name for the fallback variant of a `#[non_exhaustive]` enum, made unique in case Rust already
+/// has a variant called `Unknown`.
+fn unknown_name(preferred: &str, taken: impl IntoIterator<Item = String>) -> String {
+    let taken = taken.into_iter().collect::<Vec<_>>();
+    let mut name = preferred.to_string();
+    while taken.contains(&name) {
+        name = format!("{name}Value");
+    }
+    name
+}
@@ -7,16 +7,11 @@ use crate::gen::render::AsRenderable;
 
 use super::oracle::AsCodeType;
 use super::render::TypeHelperRenderer;
+use super::types::lower_arg;
 
 pub fn generate_function(func: &Function, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
-    let arguments = func.arguments();
-    let has_defaults = arguments.iter().any(|arg| arg.default_value().is_some());
-
-    let args = if !has_defaults {
-        quote!($(for arg in &arguments => $(&arg.as_renderable().render_type(&arg.as_type(), type_helper)) $(DartCodeOracle::var_name(arg.name())),))
-    } else {
-        generate_function_args_with_defaults(&arguments, type_helper)
-    };
+    let docs = &DartCodeOracle::docstring(func.docstring());
+    let args = generate_callable_args(&func.arguments(), func.is_async(), type_helper);
 
     let (ret, lifter) = if let Some(ret) = func.return_type() {
         (
@@ -36,6 +31,7 @@ pub fn generate_function(func: &Function, type_helper: &dyn TypeHelperRenderer)
     } else {
         quote!(null)
     };
+    let function_name = DartCodeOracle::function_name_arg(func.name());
 
     // Use centralized callback-aware argument lowering
     if func.is_async() {
@@ -52,46 +48,75 @@ pub fn generate_function(func: &Function, type_helper: &dyn TypeHelperRenderer)
         };
 
         quote!(
+            $docs
             Future<$ret> $(DartCodeOracle::fn_name(func.name()))($args) {
                 return uniffiRustCallAsync(
                   () => $(func.ffi_func().name())(
-                    $(for arg in &func.arguments() => $(DartCodeOracle::lower_arg_with_callback_handling(arg)),)
+                    $(for arg in &func.arguments() => $(lower_arg(arg, type_helper)),)
                   ),
                   $(DartCodeOracle::async_poll(func, type_helper.get_ci())),
+                  $(DartCodeOracle::async_cancel(func, type_helper.get_ci())),
                   $(DartCodeOracle::async_complete(func, type_helper.get_ci())),
                   $(DartCodeOracle::async_free(func, type_helper.get_ci())),
                   $async_lifter,
                   $error_handler,
+                  cancellationToken$(&function_name),
                 );
             }
         )
     } else if ret == quote!(void) {
         quote!(
+            $docs
             $ret $(DartCodeOracle::fn_name(func.name()))($args) {
                 return rustCall((status) {
                     $(func.ffi_func().name())(
-                        $(for arg in &func.arguments() => $(DartCodeOracle::lower_arg_with_callback_handling(arg)),) status
+                        $(for arg in &func.arguments() => $(lower_arg(arg, type_helper)),) status
                     );
-                }, $error_handler);
+                }, $error_handler$(&function_name));
             }
         )
     } else {
         quote!(
+            $docs
             $ret $(DartCodeOracle::fn_name(func.name()))($args) {
                 return rustCallWithLifter(
                     (status) => $(func.ffi_func().name())(
-                        $(for arg in &func.arguments() => $(DartCodeOracle::lower_arg_with_callback_handling(arg)),) status
+                        $(for arg in &func.arguments() => $(lower_arg(arg, type_helper)),) status
                     ),
                     $lifter,
-                    $error_handler
+                    $error_handler$(&function_name)
                 );
             }
         )
     }
 }
 
+/// Dart parameter list for a function, method or constructor. Defaulted arguments become
+/// optional named parameters, and async callables take an optional cancellation token.
+pub(super) fn generate_callable_args(
+    arguments: &[&uniffi_bindgen::interface::Argument],
+    is_async: bool,
+    type_helper: &dyn TypeHelperRenderer,
+) -> dart::Tokens {
+    let has_defaults = arguments.iter().any(|arg| arg.default_value().is_some());
+
+    // Async functions take an optional cancellation token as a trailing named parameter
+    let extra_named: Vec<dart::Tokens> = if is_async {
+        vec![DartCodeOracle::cancellation_token_param()]
+    } else {
+        vec![]
+    };
+
+    if !has_defaults && extra_named.is_empty() {
+        quote!($(for arg in arguments => $(&arg.as_renderable().render_type(&arg.as_type(), type_helper)) $(DartCodeOracle::var_name(arg.name())),))
+    } else {
+        generate_function_args_with_defaults(arguments, extra_named, type_helper)
+    }
+}
+
 fn generate_function_args_with_defaults(
     arguments: &[&uniffi_bindgen::interface::Argument],
+    extra_named: Vec<dart::Tokens>,
     type_helper: &dyn TypeHelperRenderer,
 ) -> dart::Tokens {
     let required_args: Vec<_> = arguments
@@ -124,6 +149,8 @@ fn generate_function_args_with_defaults(
         }
     }
 
+    parts.extend(extra_named);
+
     let required_count = required_args.len();
     if required_count == 0 {
         quote!({$(for (i, part) in parts.iter().enumerate() => $(if i > 0 => , )$part)})
@@ -3,18 +3,19 @@ use std::collections::HashSet;
 use std::io::Read;
 use std::process::Command;
 
-use anyhow::Result;
-use camino::Utf8Path;
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
 
 use genco::fmt;
 use genco::prelude::*;
 use serde::{Deserialize, Serialize};
 use toml;
-use uniffi_bindgen::BindgenCrateConfigSupplier;
 use uniffi_bindgen::Component;
+use uniffi_bindgen::{BindgenCrateConfigSupplier, BindgenLoader, GenerationSettings};
 // use uniffi_bindgen::MergeWith;
 use self::render::Renderer;
 use self::types::TypeHelpersRenderer;
+use self::value_methods::ValueTypeMethods;
 use crate::gen::oracle::DartCodeOracle;
 use uniffi_bindgen::{BindingGenerator, ComponentInterface};
 
@@ -29,8 +30,10 @@ mod oracle;
 mod primitives;
 mod records;
 mod render;
+mod runtime;
 pub mod stream;
 mod types;
+mod value_methods;
 
 pub use code_type::CodeType;
 
@@ -41,6 +44,92 @@ pub struct Config {
     #[serde(default)]
     external_packages: HashMap<String, String>,
     asset_id: Option<String>,
+    #[serde(default)]
+    custom_types: HashMap<String, CustomTypeConfig>,
+    #[serde(default)]
+    callback_interfaces: HashMap<String, CallbackInterfaceConfig>,
+    #[serde(default)]
+    objects: HashMap<String, ObjectConfig>,
+    /// Count live objects, outstanding RustBuffers and callback handles in the generated
+    /// runtime and expose them through `uniffiDebugStats()`.
+    #[serde(default)]
+    debug_tracking: bool,
+    /// Not configurable, filled in from the library metadata by [generate_library_bindings].
+    #[serde(skip)]
+    value_type_methods: ValueTypeMethods,
+}
+
+/// Per-type configuration read from `[bindings.dart.custom_types.<Name>]`.
+///
+/// `lift` and `lower` are Dart expressions where `{}` is replaced by the value being converted.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CustomTypeConfig {
+    #[serde(default)]
+    imports: Vec<String>,
+    type_name: Option<String>,
+    #[serde(alias = "into_custom")]
+    lift: String,
+    #[serde(alias = "from_custom")]
+    lower: String,
+}
+
+impl CustomTypeConfig {
+    pub fn imports(&self) -> &[String] {
+        &self.imports
+    }
+
+    pub fn type_name(&self) -> Option<&str> {
+        self.type_name.as_deref()
+    }
+
+    /// Render the `lift` expression for the given builtin value.
+    pub fn lift_expr(&self, value: &str) -> String {
+        self.lift.replace("{}", value)
+    }
+
+    /// Render the `lower` expression for the given custom value.
+    pub fn lower_expr(&self, value: &str) -> String {
+        self.lower.replace("{}", value)
+    }
+}
+
+/// Per-interface configuration read from `[bindings.dart.callback_interfaces.<Name>]`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CallbackInterfaceConfig {
+    #[serde(default)]
+    dispatch: CallbackDispatch,
+}
+
+impl CallbackInterfaceConfig {
+    pub fn dispatch(&self) -> CallbackDispatch {
+        self.dispatch
+    }
+}
+
+/// Per-object configuration read from `[bindings.dart.objects.<Name>]`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ObjectConfig {
+    /// Approximate native memory held by one instance in bytes, reported to the Dart GC so
+    /// large Rust objects are collected sooner.
+    external_size: Option<usize>,
+}
+
+/// How Rust reaches the Dart implementation of a callback interface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackDispatch {
+    /// Calls must happen on the thread of the isolate that registered the implementation.
+    #[default]
+    IsolateLocal,
+    /// Calls may come from any thread. Void methods are posted to the owning isolate. Methods
+    /// returning a value run straight away on the thread the isolate is blocked in Rust on, and
+    /// block any other thread until the isolate has answered from its event loop. While the
+    /// isolate is blocked in a Rust call, calls from other threads fail with an unexpected error
+    /// instead. Only one isolate may register implementations. See the README for details.
+    ///
+    /// Relies on `NativeCallable.isolateGroupBound` and `@pragma("vm:shared")`, which are
+    /// experimental in Dart 3.10.
+    ThreadSafe,
 }
 
 impl From<&ComponentInterface> for Config {
@@ -50,6 +139,11 @@ impl From<&ComponentInterface> for Config {
             cdylib_name: Some(ci.namespace().to_owned()),
             external_packages: HashMap::new(),
             asset_id: None,
+            custom_types: HashMap::new(),
+            callback_interfaces: HashMap::new(),
+            objects: HashMap::new(),
+            debug_tracking: false,
+            value_type_methods: ValueTypeMethods::default(),
         }
     }
 }
@@ -81,6 +175,31 @@ impl Config {
             format!("uniffi:{}", self.cdylib_name())
         }
     }
+
+    pub fn custom_type(&self, name: &str) -> Option<&CustomTypeConfig> {
+        self.custom_types.get(name)
+    }
+
+    pub fn callback_dispatch(&self, name: &str) -> CallbackDispatch {
+        self.callback_interfaces
+            .get(name)
+            .map(CallbackInterfaceConfig::dispatch)
+            .unwrap_or_default()
+    }
+
+    pub fn external_size(&self, name: &str) -> Option<usize> {
+        self.objects
+            .get(name)
+            .and_then(|object| object.external_size)
+    }
+
+    pub fn debug_tracking(&self) -> bool {
+        self.debug_tracking
+    }
+
+    pub(crate) fn value_type_methods(&self) -> &ValueTypeMethods {
+        &self.value_type_methods
+    }
 }
 
 pub struct DartWrapper<'a> {
@@ -91,7 +210,7 @@ pub struct DartWrapper<'a> {
 
 impl<'a> DartWrapper<'a> {
     pub fn new(ci: &'a ComponentInterface, config: &'a Config) -> Self {
-        let type_renderer = TypeHelpersRenderer::new(ci);
+        let type_renderer = TypeHelpersRenderer::new(ci, config);
         DartWrapper {
             ci,
             config,
@@ -105,11 +224,19 @@ impl<'a> DartWrapper<'a> {
         let (type_helper_code, functions_definitions) = &self.type_renderer.render();
 
         // Generate @Native external function definitions
-        fn uniffi_function_definitions(ci: &ComponentInterface, asset_id: &str) -> dart::Tokens {
+        fn uniffi_function_definitions(
+            ci: &ComponentInterface,
+            value_type_methods: &ValueTypeMethods,
+            asset_id: &str,
+        ) -> dart::Tokens {
             let mut definitions = quote!();
             let mut defined_functions = HashSet::new(); // Track defined function names
 
-            for fun in ci.iter_ffi_function_definitions() {
+            let value_type_functions = value_type_methods.iter_ffi_function_definitions().cloned();
+            for fun in ci
+                .iter_ffi_function_definitions()
+                .chain(value_type_functions)
+            {
                 let fun_name = fun.name().to_owned();
 
                 // Check for duplicate function names
@@ -169,6 +296,17 @@ impl<'a> DartWrapper<'a> {
                 });
             }
 
+            // Checksums of record and enum members are not part of `ci`, declare them by hand
+            for (name, _) in value_type_methods.iter_checksums() {
+                definitions.append(quote! {
+                    @Native<Uint16 Function()>(
+                      assetId: $asset_id
+                    )
+                    external int $name();
+                    $['\n']
+                });
+            }
+
             definitions
         }
 
@@ -189,7 +327,11 @@ impl<'a> DartWrapper<'a> {
             $(functions_definitions)
 
             // FFI function definitions using @Native
-            $(uniffi_function_definitions(self.ci, "_uniffiAssetId"))
+            $(uniffi_function_definitions(self.ci, self.config.value_type_methods(), "_uniffiAssetId"))
+
+            $(if runtime::uses_runtime(self.ci, self.config) {
+                $(runtime::generate_runtime_definitions("_uniffiAssetId"))
+            })
 
             // API version and checksum validation
             void _checkApiVersion() {
@@ -206,6 +348,11 @@ impl<'a> DartWrapper<'a> {
                       throw UniffiInternalError.panicked("UniFFI API checksum mismatch");
                     }
                 )
+                $(for (name, expected_checksum) in self.config.value_type_methods().iter_checksums() =>
+                    if ($(name)() != $expected_checksum) {
+                      throw UniffiInternalError.panicked("UniFFI API checksum mismatch");
+                    }
+                )
             }
 
             void ensureInitialized() {
@@ -216,7 +363,12 @@ impl<'a> DartWrapper<'a> {
     }
 }
 
-pub struct DartBindingGenerator;
+#[derive(Debug, Default)]
+pub struct DartBindingGenerator {
+    /// The library the bindings are for, if it is built already. It is checked for the symbols
+    /// the bindings need from `uniffi_dart_runtime`.
+    pub library: Option<Utf8PathBuf>,
+}
 
 impl BindingGenerator for DartBindingGenerator {
     type Config = Config;
@@ -226,7 +378,13 @@ impl BindingGenerator for DartBindingGenerator {
         settings: &uniffi_bindgen::GenerationSettings,
         components: &[uniffi_bindgen::Component<Self::Config>],
     ) -> Result<()> {
+        let library = self.library.as_ref().map(std::fs::read).transpose()?;
         for Component { ci, config, .. } in components {
+            records::check_default_values(ci)?;
+            if let (Some(data), Some(path)) = (&library, &self.library) {
+                runtime::check_runtime_linked(ci, config, data)
+                    .with_context(|| format!("checking '{path}'"))?;
+            }
             let filename = settings.out_dir.join(format!("{}.dart", ci.namespace()));
             let tokens = DartWrapper::new(ci, config).generate();
             let file = std::fs::File::create(filename)?;
@@ -297,7 +455,7 @@ impl BindgenCrateConfigSupplier for LocalConfigSupplier {
     }
 }
 
-pub struct ConfigFileSupplier(String);
+pub struct ConfigFileSupplier(pub(crate) String);
 impl BindgenCrateConfigSupplier for ConfigFileSupplier {
     fn get_udl(&self, _crate_name: &str, _udl_name: &str) -> Result<String> {
         // We don't have UDL in library mode, return empty
@@ -335,21 +493,20 @@ pub fn generate_dart_bindings(
                 Box::new(LocalConfigSupplier(udl_file.to_string()))
             };
 
-        uniffi_bindgen::library_mode::generate_bindings(
+        generate_library_bindings(
             library_file,
             None, // crate name filter
-            &DartBindingGenerator {},
             config_supplier.as_ref(),
-            None,
             out_dir_override.unwrap(),
             true,
-        )?;
-        Ok(())
+        )
     } else {
         // Note: library_file is needed by uniffi_bindgen to extract metadata from proc macros,
         // even though we don't use it for DynamicLibrary.open() anymore (Native Assets handle that)
         uniffi_bindgen::generate_external_bindings(
-            &DartBindingGenerator {},
+            &DartBindingGenerator {
+                library: Some(library_file.to_owned()),
+            },
             udl_file,
             config_file_override,
             out_dir_override,
@@ -359,3 +516,59 @@ pub fn generate_dart_bindings(
         )
     }
 }
+
+/// Generate bindings for the crates in `library_path`.
+///
+/// This follows `uniffi_bindgen::library_mode::generate_bindings`, except that methods and
+/// constructors on records and enums are taken out of the metadata first, the
+/// `ComponentInterface` would reject them.
+pub fn generate_library_bindings(
+    library_path: &Utf8Path,
+    crate_name: Option<&str>,
+    config_supplier: &dyn BindgenCrateConfigSupplier,
+    out_dir: &Utf8Path,
+    try_format_code: bool,
+) -> anyhow::Result<()> {
+    let generator = DartBindingGenerator {
+        library: Some(library_path.to_owned()),
+    };
+    let loader = BindgenLoader::new(config_supplier);
+    let mut constructors = vec![];
+    let mut metadata = loader
+        .load_metadata_specialized(library_path, |_, data| {
+            value_methods::read_library_metadata(data, &mut constructors)
+        })
+        .with_context(|| format!("finding components in '{library_path}'"))?;
+    let mut value_type_methods = metadata
+        .iter_mut()
+        .map(|(name, group)| {
+            let methods = ValueTypeMethods::split_from(group, &mut constructors)?;
+            Ok((name.clone(), methods))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let cis = loader.load_cis(metadata)?;
+    let mut components = loader.load_components(cis, |ci, toml| {
+        let mut config = generator.new_config(&toml).context("loading toml")?;
+        if let Some(methods) = value_type_methods.remove(ci.crate_name()) {
+            config.value_type_methods = methods;
+        }
+        Ok(config)
+    })?;
+    if let Some(crate_name) = crate_name {
+        components.retain(|c| c.ci.crate_name() == crate_name);
+        if components.is_empty() {
+            bail!("Crate {crate_name} not found in {library_path}");
+        }
+    }
+
+    let settings = GenerationSettings {
+        out_dir: out_dir.to_owned(),
+        try_format_code,
+        cdylib: uniffi_bindgen::library_mode::calc_cdylib_name(library_path).map(ToOwned::to_owned),
+    };
+    generator.update_component_configs(&settings, &mut components)?;
+
+    std::fs::create_dir_all(out_dir)?;
+    generator.write_bindings(&settings, &components)
+}
@@ -2,19 +2,24 @@ use genco::prelude::*;
 use std::fmt::Debug;
 
 use crate::gen::callback_interface::{
-    generate_callback_functions, generate_callback_interface,
-    generate_callback_interface_vtable_init_function, generate_callback_vtable_interface,
+    generate_callback_functions, generate_callback_interface_vtable_init_function,
+    generate_callback_methods_definitions, generate_callback_methods_signatures,
+    generate_callback_vtable_interface,
 };
 use crate::gen::CodeType;
 use heck::ToLowerCamelCase;
 use std::string::ToString;
-use uniffi_bindgen::interface::{AsType, Method, Object, ObjectImpl, UniffiTrait};
+use uniffi_bindgen::interface::{
+    AsType, Callable, Method, Object, ObjectImpl, UniffiTrait, UniffiTraitMethods,
+};
 use uniffi_bindgen::pipeline::general::nodes::Literal;
 
 use crate::gen::oracle::{AsCodeType, DartCodeOracle};
 use crate::gen::render::AsRenderable;
 use crate::gen::render::{Renderable, TypeHelperRenderer};
+use crate::gen::types::{debug_tracking_hook, hand_over_arg, lower_arg};
 
+use super::functions::generate_callable_args;
 use super::stream::generate_stream;
 
 #[derive(Debug)]
@@ -66,34 +71,7 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
     type_helper.include_once_check(obj.name(), &obj.as_type());
 
     if obj.has_callback_interface() {
-        let interface = generate_callback_interface(
-            obj.name(),
-            &obj.as_codetype().ffi_converter_name(),
-            &obj.methods(),
-            type_helper,
-        );
-        let vtable_interface = generate_callback_vtable_interface(obj.name(), &obj.methods());
-        let functions = generate_callback_functions(obj.name(), &obj.methods(), type_helper);
-        let fallback_namespace = {
-            let namespace = type_helper
-                .get_ci()
-                .namespace_for_type(&obj.as_type())
-                .expect("object should have namespace");
-            namespace.to_string()
-        };
-        let ffi_module =
-            DartCodeOracle::infer_ffi_module(type_helper.get_ci(), move || fallback_namespace);
-        let vtable_init = generate_callback_interface_vtable_init_function(
-            obj.name(),
-            &obj.methods(),
-            &ffi_module,
-        );
-        return quote!(
-            $interface
-            $vtable_interface
-            $functions
-            $vtable_init
-        );
+        return generate_foreign_trait_object(obj, type_helper);
     } else if obj.is_trait_interface() {
         return generate_trait_object(obj, type_helper);
     }
@@ -101,9 +79,8 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
     let cls_name = &DartCodeOracle::class_name(obj.name());
     let interface_name = DartCodeOracle::object_interface_name(type_helper.get_ci(), obj);
     let interface_definition = generate_object_interface(obj, &interface_name, type_helper);
-    let finalizer_cls_name = &format!("{cls_name}Finalizer");
-    let ffi_object_free_name = obj.ffi_object_free().name();
-    let ffi_object_clone_name = obj.ffi_object_clone().name();
+    let attach_finalizer = attach_finalizer(obj, type_helper);
+    let (track_created, track_released) = object_tracking_hooks(cls_name, type_helper);
 
     // Stream workaround, make it more elegant later
 
@@ -125,7 +102,8 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
 
         // Check if function can throw errors
         let error_handler = if let Some(error_type) = constructor.throws_type() {
-            let error_name = DartCodeOracle::class_name(error_type.name().unwrap_or("UnknownError"));
+            let error_name =
+                DartCodeOracle::class_name(error_type.name().unwrap_or("UnknownError"));
             // Use the consistent Exception naming for error handlers
             let handler_name = format!("{}ErrorHandler", error_name.to_lower_camel_case());
             quote!($(handler_name))
@@ -133,12 +111,12 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
             quote!(null)
         };
 
-        let dart_params = quote!($(for arg in constructor.arguments() =>
-            $(DartCodeOracle::dart_type_label(Some(&arg.as_type()))) $(DartCodeOracle::var_name(arg.name())),
-        ));
+        let function_name =
+            DartCodeOracle::function_name_arg(&format!("{}::{constructor_name}", obj.name()));
+        let dart_params = generate_callable_args(&constructor.arguments(), false, type_helper);
 
         let ffi_call_args = quote!($(for arg in constructor.arguments() =>
-            $(DartCodeOracle::type_lower_fn(&arg.as_type(), quote!($(DartCodeOracle::var_name(arg.name()))))),)
+            $(lower_arg(arg, type_helper)),)
         );
 
         // Ensure argument types are included
@@ -148,13 +126,15 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
 
         quote! {
             // Public constructor
+            $(DartCodeOracle::docstring(constructor.docstring()))
             $dart_constructor_decl($dart_params) : _ptr = rustCall((status) =>
                 $ffi_func_name(
                     $ffi_call_args status
                 ),
-                $error_handler
+                $error_handler$function_name
             ) {
-                 _$finalizer_cls_name.attach(this, _ptr, detach: this);
+                 $(&attach_finalizer)
+                 $(&track_created)
             }
         }
     });
@@ -169,8 +149,8 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
         quote! {
             class $(&error_handler_name) extends UniffiRustCallStatusErrorHandler {
                 @override
-                Exception lift(RustBuffer errorBuf) {
-                    return $(cls_name).read(errorBuf.asUint8List()).value;
+                Exception lift(RustBuffer errorBuf, [String? functionName]) {
+                    return liftFromRustBuffer(errorBuf, (buf) => $(cls_name).read(buf, functionName));
                 }
             }
 
@@ -184,8 +164,10 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
     if !obj.is_trait_interface() {
         implements.push(interface_name.clone());
     }
-    if is_error_interface && !implements.iter().any(|entry| entry == "Exception") {
-        implements.push("Exception".to_string());
+    // Keeps the object alive until FFI calls using its pointer have returned
+    implements.push("Finalizable".to_string());
+    if obj.uniffi_trait_methods().ord_cmp.is_some() {
+        implements.push(format!("Comparable<{cls_name}>"));
     }
 
     for trait_impl in obj.trait_impls() {
@@ -201,11 +183,10 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
         }
     }
 
-    let implements_clause = if implements.is_empty() {
-        quote!()
-    } else {
-        quote!( implements $(for imp in implements.iter() join (, ) => $(imp)))
-    };
+    let class_clause = quote!(
+        $(if is_error_interface { extends UniffiException })
+        $(if !implements.is_empty() { implements $(for imp in implements.iter() join (, ) => $(imp)) })
+    );
 
     // Generate toString() method for error interfaces
     let has_display_trait = obj
@@ -229,46 +210,52 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
 
     let trait_methods = generate_trait_helpers(obj, type_helper);
 
+    // Errors remember the Rust function that raised them
+    let (function_name_param, function_name_arg) = if is_error_interface {
+        (
+            quote!(, [String? rustFunctionName]),
+            quote!(, rustFunctionName),
+        )
+    } else {
+        (quote!(), quote!())
+    };
+
     quote! {
         $interface_definition
 
-        final _$finalizer_cls_name = Finalizer<Pointer<Void>>((ptr) {
-          rustCall((status) => $ffi_object_free_name(ptr, status));
-        });
-
-        class $cls_name $implements_clause {
+        $(DartCodeOracle::docstring(obj.docstring()))
+        class $cls_name $class_clause {
             late final Pointer<Void> _ptr;
 
             // Private constructor for internal use / lift
-            $cls_name._(this._ptr) {
-                _$finalizer_cls_name.attach(this, _ptr, detach: this);
+            $cls_name._(this._ptr$(&function_name_param))$(if is_error_interface { : super(rustFunctionName: rustFunctionName)}) {
+                $(&attach_finalizer)
+                $(&track_created)
             }
 
             // Public constructors generated from UDL
             $( for ctor_def in constructor_definitions => $ctor_def )
 
             // Factory for lifting pointers
-            factory $cls_name.lift(Pointer<Void> ptr) {
-                return $cls_name._(ptr);
+            factory $cls_name.lift(Pointer<Void> ptr$(&function_name_param)) {
+                return $cls_name._(ptr$(&function_name_arg));
             }
 
             static Pointer<Void> lower($cls_name value) {
                 return value.uniffiClonePointer();
             }
 
-            Pointer<Void> uniffiClonePointer() {
-                return rustCall((status) => $ffi_object_clone_name(_ptr, status));
-            }
+            $(generate_pointer_lifecycle(cls_name, obj, &track_released, false))
 
             // A Rust pointer is 8 bytes
             static int allocationSize($cls_name value) {
                 return 8;
             }
 
-            static LiftRetVal<$cls_name> read(Uint8List buf) {
+            static LiftRetVal<$cls_name> read(Uint8List buf$(&function_name_param)) {
                 final handle = buf.buffer.asByteData(buf.offsetInBytes).getInt64(0);
                 final pointer = Pointer<Void>.fromAddress(handle);
-                return LiftRetVal($cls_name.lift(pointer), 8);
+                return LiftRetVal($cls_name.lift(pointer$(&function_name_arg)), 8);
             }
 
             static int write($cls_name value, Uint8List buf) {
@@ -277,12 +264,17 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
                 return 8;
             }
 
-            void dispose() {
-                _$finalizer_cls_name.detach(this);
-                rustCall((status) => $ffi_object_free_name(_ptr, status));
-            }
-
             $to_string_method
+
+            $(if is_error_interface {
+                @override
+                String get rustTypeName => $(format!("\"{}\"", obj.name()));
+            })
+
+            $(if is_error_interface && has_display_trait {
+                @override
+                String? get rustMessage => toString();
+            })
             $trait_methods
 
             $(for mt in &obj.methods() => $(generate_method(mt, type_helper)))
@@ -294,12 +286,134 @@ pub fn generate_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> da
     }
 }
 
-#[allow(unused_variables)]
+/// `uniffiClonePointer` and an idempotent `dispose` for a class holding a Rust pointer in
+/// `_ptr`. Every call on the object lowers it through `uniffiClonePointer`, which throws a
+/// `StateError` once the object has been disposed instead of handing Rust a dangling pointer.
+fn generate_pointer_lifecycle(
+    cls_name: &str,
+    obj: &Object,
+    track_released: &dart::Tokens,
+    is_override: bool,
+) -> dart::Tokens {
+    let ffi_object_free_name = obj.ffi_object_free().name();
+    let ffi_object_clone_name = obj.ffi_object_clone().name();
+    let disposed_message = format!("\"{cls_name} has been disposed\"");
+
+    quote! {
+        bool _disposed = false;
+
+        // Lets `_uniffiObjectFinalizer` free the Rust object, released once the object is disposed
+        late final Pointer<Void> _finalizerToken;
+
+        $(if is_override { @override })
+        bool get isDisposed => _disposed;
+
+        Pointer<Void> uniffiClonePointer() {
+            if (_disposed) {
+                throw StateError($(&disposed_message));
+            }
+            return rustCall((status) => $ffi_object_clone_name(_ptr, status));
+        }
+
+        $(if is_override { @override })
+        void dispose() {
+            if (_disposed) {
+                return;
+            }
+            _disposed = true;
+            _uniffiObjectFinalizer.detach(this);
+            uniffi_dart_finalizer_token_free(_finalizerToken);
+            $track_released
+            rustCall((status) => $ffi_object_free_name(_ptr, status));
+        }
+    }
+}
+
+/// Attaches `this` to `_uniffiObjectFinalizer`, so the Rust object is freed if the wrapper is
+/// garbage collected without being disposed. The `external_size` configured for the object is
+/// reported to the GC.
+fn attach_finalizer(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
+    let ffi_object_free_name = obj.ffi_object_free().name();
+    let token = quote! {
+        _finalizerToken = uniffi_dart_finalizer_token_new(
+            Native.addressOf<NativeFunction<Void Function(Pointer<Void>, Pointer<RustCallStatus>)>>($ffi_object_free_name).cast(),
+            _ptr);
+    };
+    match type_helper.get_config().external_size(obj.name()) {
+        Some(size) => quote! {
+            $token
+            _uniffiObjectFinalizer.attach(this, _finalizerToken, detach: this, externalSize: $size);
+        },
+        None => quote! {
+            $token
+            _uniffiObjectFinalizer.attach(this, _finalizerToken, detach: this);
+        },
+    }
+}
+
+/// Statements counting an object of class `cls_name` in and out of the debug stats, empty
+/// unless `debug_tracking` is enabled.
+fn object_tracking_hooks(
+    cls_name: &str,
+    type_helper: &dyn TypeHelperRenderer,
+) -> (dart::Tokens, dart::Tokens) {
+    let name = format!("\"{cls_name}\"");
+    (
+        debug_tracking_hook(
+            type_helper,
+            quote! {
+                _uniffiTrackObject($(&name), 1);
+                _uniffiObjectTracker.attach(this, $(&name), detach: this);
+            },
+        ),
+        debug_tracking_hook(
+            type_helper,
+            quote! {
+                _uniffiObjectTracker.detach(this);
+                _uniffiTrackObject($(&name), -1);
+            },
+        ),
+    )
+}
+
 pub fn generate_method(func: &Method, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
+    generate_method_with_receiver(func, quote!(uniffiClonePointer()), type_helper)
+}
+
+/// Render a method whose first FFI argument is `receiver`, the lowered `this`.
+pub(super) fn generate_method_with_receiver(
+    func: &Method,
+    receiver: dart::Tokens,
+    type_helper: &dyn TypeHelperRenderer,
+) -> dart::Tokens {
     // if func.takes_self_by_arc() {} // TODO: Do something about this condition
-    let args = quote!($(for arg in &func.arguments() => $(&arg.as_renderable().render_type(&arg.as_type(), type_helper)) $(DartCodeOracle::var_name(arg.name())),));
+    let name = DartCodeOracle::fn_name(func.name());
+    generate_ffi_call(
+        func,
+        func.ffi_func().name(),
+        quote!($receiver,),
+        |ret| quote!($ret $(&name)),
+        &format!("{}::{}", func.object_name(), func.name()),
+        type_helper,
+    )
+}
+
+/// Render a Dart member calling `ffi_func_name` with `leading_args` in front of the lowered
+/// arguments of `callable`. `declaration` renders what goes before the parameter list from the
+/// Dart return type, `Future<T>` for async calls.
+#[allow(unused_variables)]
+pub(super) fn generate_ffi_call(
+    callable: &impl Callable,
+    ffi_func_name: &str,
+    leading_args: dart::Tokens,
+    declaration: impl Fn(dart::Tokens) -> dart::Tokens,
+    rust_name: &str,
+    type_helper: &dyn TypeHelperRenderer,
+) -> dart::Tokens {
+    let docs = &DartCodeOracle::docstring(callable.docstring());
+    let args = generate_callable_args(&callable.arguments(), callable.is_async(), type_helper);
 
-    let (ret, lifter) = if let Some(ret) = func.return_type() {
+    let (ret, lifter) = if let Some(ret) = callable.return_type() {
         (
             ret.as_renderable().render_type(ret, type_helper),
             quote!($(ret.as_codetype().lift())),
@@ -309,7 +423,7 @@ pub fn generate_method(func: &Method, type_helper: &dyn TypeHelperRenderer) -> d
     };
 
     // Check if function can throw errors
-    let error_handler = if let Some(error_type) = func.throws_type() {
+    let error_handler = if let Some(error_type) = callable.throws_type() {
         let error_name = DartCodeOracle::class_name(error_type.name().unwrap_or("UnknownError"));
         // Use the consistent Exception naming for error handlers
         let handler_name = format!("{}ErrorHandler", error_name.to_lower_camel_case());
@@ -317,10 +431,11 @@ pub fn generate_method(func: &Method, type_helper: &dyn TypeHelperRenderer) -> d
     } else {
         quote!(null)
     };
+    let function_name = DartCodeOracle::function_name_arg(rust_name);
 
-    if func.is_async() {
+    if callable.is_async() {
         // For async methods returning objects, we need to convert the int pointer to Pointer<Void>
-        let async_lifter = if let Some(ret_type) = func.return_type() {
+        let async_lifter = if let Some(ret_type) = callable.return_type() {
             match ret_type {
                 uniffi_bindgen::interface::Type::Object { .. } => {
                     quote!((ptr) => $lifter(Pointer<Void>.fromAddress(ptr)))
@@ -332,42 +447,47 @@ pub fn generate_method(func: &Method, type_helper: &dyn TypeHelperRenderer) -> d
         };
 
         quote!(
-            Future<$ret> $(DartCodeOracle::fn_name(func.name()))($args) {
+            $docs
+            $(declaration(quote!(Future<$ret>)))($args) {
                 return uniffiRustCallAsync(
-                  () => $(func.ffi_func().name())(
-                    uniffiClonePointer(),
-                    $(for arg in &func.arguments() => $(DartCodeOracle::lower_arg_with_callback_handling(arg)),)
+                  () => $ffi_func_name(
+                    $(&leading_args)
+                    $(for arg in &callable.arguments() => $(lower_arg(arg, type_helper)),)
                   ),
-                  $(DartCodeOracle::async_poll(func, type_helper.get_ci())),
-                  $(DartCodeOracle::async_complete(func, type_helper.get_ci())),
-                  $(DartCodeOracle::async_free(func, type_helper.get_ci())),
+                  $(DartCodeOracle::async_poll(callable, type_helper.get_ci())),
+                  $(DartCodeOracle::async_cancel(callable, type_helper.get_ci())),
+                  $(DartCodeOracle::async_complete(callable, type_helper.get_ci())),
+                  $(DartCodeOracle::async_free(callable, type_helper.get_ci())),
                   $async_lifter,
                   $error_handler,
+                  cancellationToken$(&function_name),
                 );
             }
 
         )
     } else if ret == quote!(void) {
         quote!(
-            $ret $(DartCodeOracle::fn_name(func.name()))($args) {
+            $docs
+            $(declaration(ret))($args) {
                 return rustCall((status) {
-                    $(func.ffi_func().name())(
-                        uniffiClonePointer(),
-                        $(for arg in &func.arguments() => $(DartCodeOracle::lower_arg_with_callback_handling(arg)),) status
+                    $ffi_func_name(
+                        $(&leading_args)
+                        $(for arg in &callable.arguments() => $(lower_arg(arg, type_helper)),) status
                     );
-                }, $error_handler);
+                }, $error_handler$(&function_name));
             }
         )
     } else {
         quote!(
-            $ret $(DartCodeOracle::fn_name(func.name()))($args) {
+            $docs
+            $(declaration(ret))($args) {
                 return rustCallWithLifter(
-                    (status) => $(func.ffi_func().name())(
-                        uniffiClonePointer(),
-                        $(for arg in &func.arguments() => $(DartCodeOracle::lower_arg_with_callback_handling(arg)),) status
+                    (status) => $ffi_func_name(
+                        $(&leading_args)
+                        $(for arg in &callable.arguments() => $(lower_arg(arg, type_helper)),) status
                     ),
                     $lifter,
-                    $error_handler
+                    $error_handler$(&function_name)
                 );
             }
         )
@@ -375,76 +495,78 @@ pub fn generate_method(func: &Method, type_helper: &dyn TypeHelperRenderer) -> d
 }
 
 fn generate_trait_helpers(obj: &Object, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
+    generate_uniffi_trait_methods(
+        &DartCodeOracle::class_name(obj.name()),
+        &obj.uniffi_trait_methods(),
+        &quote!(uniffiClonePointer()),
+        type_helper,
+    )
+}
+
+/// Dart overrides backed by the Rust implementations of the exported uniffi traits.
+/// `receiver` is the lowered `this` passed as the first argument of each call.
+pub(super) fn generate_uniffi_trait_methods(
+    cls_name: &str,
+    methods: &UniffiTraitMethods,
+    receiver: &dart::Tokens,
+    type_helper: &dyn TypeHelperRenderer,
+) -> dart::Tokens {
     let mut tokens = quote!();
-    let mut generated_display = false;
-    let mut generated_debug = false;
-    let mut generated_eq = false;
-    let mut generated_hash = false;
-
-    for trait_impl in obj.uniffi_traits() {
-        match trait_impl {
-            UniffiTrait::Display { fmt } => {
-                if generated_display {
-                    continue;
-                }
-                let call = trait_method_call(fmt, type_helper, &[]);
-                tokens.append(quote! {
-                    @override
-                    String toString() {
-                        return $call;
-                    }
-                });
-                generated_display = true;
-            }
-            UniffiTrait::Debug { fmt } => {
-                if generated_debug {
-                    continue;
-                }
-                let call = trait_method_call(fmt, type_helper, &[]);
-                tokens.append(quote! {
-                    String debugString() {
-                        return $call;
-                    }
-                });
-                generated_debug = true;
-            }
-            UniffiTrait::Eq { eq, .. } => {
-                if generated_eq {
-                    continue;
+
+    if let Some(fmt) = &methods.display_fmt {
+        let call = trait_method_call(fmt, receiver, type_helper, &[]);
+        tokens.append(quote! {
+            @override
+            String toString() {
+                return $call;
+            }
+        });
+    }
+    if let Some(fmt) = &methods.debug_fmt {
+        let call = trait_method_call(fmt, receiver, type_helper, &[]);
+        tokens.append(quote! {
+            String debugString() {
+                return $call;
+            }
+        });
+    }
+    if let Some(eq) = &methods.eq_eq {
+        let call = trait_method_call(eq, receiver, type_helper, &[quote!(other)]);
+        tokens.append(quote! {
+            @override
+            bool operator ==(Object other) {
+                if (identical(this, other)) {
+                    return true;
                 }
-                let call = trait_method_call(eq, type_helper, &[quote!(other)]);
-                tokens.append(quote! {
-                    @override
-                    bool operator ==(Object other) {
-                        if (identical(this, other)) {
-                            return true;
-                        }
-                        if (other is! $(DartCodeOracle::class_name(obj.name()))) {
-                            return false;
-                        }
-                        return $call;
-                    }
-                });
-                generated_eq = true;
-            }
-            UniffiTrait::Hash { hash } => {
-                if generated_hash {
-                    continue;
+                if (other is! $cls_name) {
+                    return false;
                 }
-                let call = trait_method_call(hash, type_helper, &[]);
-                tokens.append(quote! {
-                    @override
-                    int get hashCode {
-                        return $call;
-                    }
-                });
-                generated_hash = true;
-            }
-            UniffiTrait::Ord { .. } => {
-                // Ord trait is not currently supported in Dart bindings
-                // Skip generation for now
+                return $call;
             }
-        }
+        });
+    }
+    if let Some(hash) = &methods.hash_hash {
+        let call = trait_method_call(hash, receiver, type_helper, &[]);
+        tokens.append(quote! {
+            @override
+            int get hashCode {
+                return $call;
+            }
+        });
+    }
+    if let Some(cmp) = &methods.ord_cmp {
+        let call = trait_method_call(cmp, receiver, type_helper, &[quote!(other)]);
+        tokens.append(quote! {
+            @override
+            int compareTo($cls_name other) {
+                return $call;
+            }
+
+            bool operator <($cls_name other) => compareTo(other) < 0;
+            bool operator <=($cls_name other) => compareTo(other) <= 0;
+            bool operator >($cls_name other) => compareTo(other) > 0;
+            bool operator >=($cls_name other) => compareTo(other) >= 0;
+        });
     }
 
     tokens
@@ -452,12 +574,15 @@ fn generate_trait_helpers(obj: &Object, type_helper: &dyn TypeHelperRenderer) ->
 
 fn trait_method_call(
     method: &Method,
+    receiver: &dart::Tokens,
     type_helper: &dyn TypeHelperRenderer,
     arg_exprs: &[dart::Tokens],
 ) -> dart::Tokens {
     assert_eq!(method.arguments().len(), arg_exprs.len());
 
     let ffi_name = method.ffi_func().name();
+    let function_name =
+        DartCodeOracle::function_name_arg(&format!("{}::{}", method.object_name(), method.name()));
 
     let error_handler = if let Some(error_type) = method.throws_type() {
         let error_name = DartCodeOracle::class_name(error_type.name().unwrap_or("UnknownError"));
@@ -470,7 +595,11 @@ fn trait_method_call(
     let mut lowered_args = Vec::new();
     for (arg, expr) in method.arguments().into_iter().zip(arg_exprs.iter()) {
         type_helper.include_once_check(&arg.as_codetype().canonical_name(), &arg.as_type());
-        lowered_args.push(DartCodeOracle::type_lower_fn(&arg.as_type(), expr.clone()));
+        lowered_args.push(hand_over_arg(
+            &arg.as_type(),
+            DartCodeOracle::type_lower_fn(&arg.as_type(), expr.clone()),
+            type_helper,
+        ));
     }
 
     if let Some(ret) = method.return_type() {
@@ -479,23 +608,23 @@ fn trait_method_call(
         quote!(
             rustCallWithLifter(
                 (status) => $ffi_name(
-                    uniffiClonePointer(),
+                    $receiver,
                     $(for arg in lowered_args => $arg,)
                     status
                 ),
                 $lifter,
-                $error_handler
+                $error_handler$function_name
             )
         )
     } else {
         quote!(
             rustCall((status) {
                 $ffi_name(
-                    uniffiClonePointer(),
+                    $receiver,
                     $(for arg in lowered_args => $arg,)
                     status
                 );
-            }, $error_handler)
+            }, $error_handler$function_name)
         )
     }
 }
@@ -505,10 +634,9 @@ fn generate_trait_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) ->
 
     let cls_name = &DartCodeOracle::class_name(obj.name());
     let impl_name = format!("_{cls_name}Impl");
-    let finalizer_field = format!("_{cls_name}ImplFinalizer");
+    let attach_finalizer = attach_finalizer(obj, type_helper);
 
-    let ffi_object_free_name = obj.ffi_object_free().name();
-    let ffi_object_clone_name = obj.ffi_object_clone().name();
+    let (track_created, track_released) = object_tracking_hooks(cls_name, type_helper);
 
     let abstract_methods = obj
         .methods()
@@ -521,6 +649,7 @@ fn generate_trait_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) ->
         .map(|method| generate_method(method, type_helper));
 
     quote! {
+        $(DartCodeOracle::docstring(obj.docstring()))
         abstract class $cls_name {
             factory $cls_name.lift(Pointer<Void> ptr) {
                 // UniFFI 0.30.0: Check if handle is from foreign side (lowest bit set)
@@ -564,32 +693,22 @@ fn generate_trait_object(obj: &Object, type_helper: &dyn TypeHelperRenderer) ->
 
             void dispose();
 
+            bool get isDisposed;
+
             $(for method in abstract_methods => $method)
         }
 
-        final class $(&impl_name) implements $cls_name {
+        final class $(&impl_name) implements $cls_name, Finalizable {
             $(&impl_name)._internal(this._ptr) {
-                $(&finalizer_field).attach(this, _ptr, detach: this);
+                $(&attach_finalizer)
+                $(&track_created)
             }
 
-            static final Finalizer<Pointer<Void>> $(&finalizer_field) =
-                Finalizer<Pointer<Void>>((ptr) {
-                    rustCall((status) => $ffi_object_free_name(ptr, status));
-                });
-
             Pointer<Void> _ptr;
 
             static int allocationSize($(&impl_name) _) => 8;
 
-            Pointer<Void> uniffiClonePointer() {
-                return rustCall((status) => $ffi_object_clone_name(_ptr, status));
-            }
-
-            @override
-            void dispose() {
-                $(&finalizer_field).detach(this);
-                rustCall((status) => $ffi_object_free_name(_ptr, status));
-            }
+            $(generate_pointer_lifecycle(cls_name, obj, &track_released, true))
 
             $(for method in concrete_methods => $method)
         }
@@ -607,12 +726,15 @@ fn generate_object_interface(
         .map(|method| generate_interface_method(method, type_helper))
         .collect();
 
+    let docs = DartCodeOracle::docstring(obj.docstring());
     if method_tokens.is_empty() {
         quote! {
+            $docs
             abstract class $(interface_name) {}
         }
     } else {
         quote! {
+            $docs
             abstract class $(interface_name) {
                 $(for method in method_tokens => $method)
             }
@@ -624,25 +746,12 @@ fn generate_interface_method(
     method: &Method,
     type_helper: &dyn TypeHelperRenderer,
 ) -> dart::Tokens {
-    let arg_tokens: Vec<dart::Tokens> = method
-        .arguments()
-        .into_iter()
-        .map(|arg| {
-            let ty = arg.as_renderable().render_type(&arg.as_type(), type_helper);
-            let name = DartCodeOracle::var_name(arg.name());
-            quote!($ty $name)
-        })
-        .collect();
-
-    let params = if arg_tokens.is_empty() {
-        quote!()
-    } else {
-        quote!($(for arg in arg_tokens.iter() join (, ) => $arg))
-    };
+    let params = generate_callable_args(&method.arguments(), method.is_async(), type_helper);
     let ret_type = method_return_type_tokens(method, type_helper);
     let method_name = DartCodeOracle::fn_name(method.name());
 
     quote!(
+        $(DartCodeOracle::docstring(method.docstring()))
         $ret_type $method_name($params);
     )
 }
@@ -663,3 +772,124 @@ fn method_return_type_tokens(
         base
     }
 }
+
+/// Traits exported `with_foreign` can be implemented on either side of the FFI. Rust
+/// implementations are wrapped in a private `_Impl` class, Dart implementations are stored
+/// in a handle map and exposed to Rust through a vtable, just like callback interfaces.
+fn generate_foreign_trait_object(
+    obj: &Object,
+    type_helper: &dyn TypeHelperRenderer,
+) -> dart::Tokens {
+    type_helper.include_once_check(obj.name(), &obj.as_type());
+
+    let cls_name = &DartCodeOracle::class_name(obj.name());
+    let ffi_conv_name = &obj.as_codetype().ffi_converter_name();
+    let impl_name = &format!("_{cls_name}Impl");
+    let attach_finalizer = attach_finalizer(obj, type_helper);
+    let init_fn_name = &format!("init{}VTable", obj.name());
+
+    let (track_created, track_released) = object_tracking_hooks(cls_name, type_helper);
+
+    let methods = obj.methods();
+    let abstract_methods = methods
+        .iter()
+        .map(|method| generate_callback_methods_definitions(method, type_helper));
+    let concrete_methods = methods
+        .iter()
+        .map(|method| generate_method(method, type_helper));
+
+    let vtable_interface = generate_callback_vtable_interface(obj.name(), &methods);
+    let functions = generate_callback_functions(obj.name(), &methods, type_helper);
+    let fallback_namespace = {
+        let namespace = type_helper
+            .get_ci()
+            .namespace_for_type(&obj.as_type())
+            .expect("object should have namespace");
+        namespace.to_string()
+    };
+    let ffi_module =
+        DartCodeOracle::infer_ffi_module(type_helper.get_ci(), move || fallback_namespace);
+    let vtable_init = generate_callback_interface_vtable_init_function(
+        obj.name(),
+        &methods,
+        &ffi_module,
+        type_helper,
+    );
+
+    quote! {
+        $(DartCodeOracle::docstring(obj.docstring()))
+        abstract class $cls_name {
+            $(for method in abstract_methods => $method)
+
+            // Dart implementations hold no native resources, Rust implementations override these.
+            void dispose() {}
+
+            bool get isDisposed => false;
+        }
+
+        final class $impl_name implements $cls_name, Finalizable {
+            $impl_name._internal(this._ptr) {
+                $(&attach_finalizer)
+                $(&track_created)
+            }
+
+            Pointer<Void> _ptr;
+
+            $(generate_pointer_lifecycle(cls_name, obj, &track_released, true))
+
+            $(for method in concrete_methods => $method)
+        }
+
+        class $ffi_conv_name {
+            static final _handleMap = UniffiHandleMap<$cls_name>($(format!("\"{cls_name}\"")));
+            static bool _vtableInitialized = false;
+
+            static $cls_name lift(Pointer<Void> handle) {
+                // UniFFI 0.30.0: handles from the foreign side have the lowest bit set.
+                // Rust hands us a fresh handle for the Dart object, which we consume here.
+                if ((handle.address & 0x1) != 0) {
+                    final obj = _handleMap.get(handle.address);
+                    _handleMap.remove(handle.address);
+                    return obj;
+                }
+                return $impl_name._internal(handle);
+            }
+
+            static Pointer<Void> lower($cls_name value) {
+                if (value is $impl_name) {
+                    return value.uniffiClonePointer();
+                }
+                _ensureVTableInitialized();
+                return Pointer<Void>.fromAddress(_handleMap.insert(value));
+            }
+
+            static void _ensureVTableInitialized() {
+                if (!_vtableInitialized) {
+                    $init_fn_name();
+                    _vtableInitialized = true;
+                }
+            }
+
+            static LiftRetVal<$cls_name> read(Uint8List buf) {
+                final handle = buf.buffer.asByteData(buf.offsetInBytes).getInt64(0);
+                final pointer = Pointer<Void>.fromAddress(handle);
+                return LiftRetVal(lift(pointer), 8);
+            }
+
+            static int write($cls_name value, Uint8List buf) {
+                final handle = lower(value);
+                buf.buffer.asByteData(buf.offsetInBytes).setInt64(0, handle.address);
+                return 8;
+            }
+
+            static int allocationSize($cls_name value) {
+                return 8;
+            }
+        }
+
+        $(generate_callback_methods_signatures(cls_name, &methods, type_helper))
+        $vtable_interface
+        $functions
+        $vtable_init
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::{bail, Result};
 use genco::lang::dart;
 use genco::quote;
 use heck::{ToLowerCamelCase, ToUpperCamelCase};
@@ -35,6 +36,31 @@ impl DartCodeOracle {
         RESERVED_IDENTIFIERS.contains(&id)
     }
 
+    /// Render a UniFFI docstring as `///` dartdoc lines, or nothing if there is none.
+    ///
+    /// Both rustdoc and dartdoc are Markdown with `[Symbol]` links, so the text is kept as is,
+    /// except for `{@` which dartdoc would run as a directive such as `{@macro}`. Line comments
+    /// cannot be closed early, so `*/` needs no treatment, but each has to end its line.
+    pub fn docstring(docstring: Option<&str>) -> dart::Tokens {
+        let mut tokens = dart::Tokens::new();
+        for line in docstring.into_iter().flat_map(str::lines) {
+            let line = line.replace("{@", "{\\@");
+            tokens.append(format!("/// {line}").trim_end().to_string());
+            tokens.push();
+        }
+        tokens
+    }
+
+    /// Whether values of this type have to go through `uniffiDeepEquals`/`uniffiDeepHash`,
+    /// because Dart collections compare by identity.
+    pub fn needs_deep_equality(ty: &Type) -> bool {
+        match ty {
+            Type::Sequence { .. } | Type::Map { .. } | Type::Bytes => true,
+            Type::Optional { inner_type } => Self::needs_deep_equality(inner_type),
+            _ => false,
+        }
+    }
+
     /// Get the idiomatic Dart rendering of a class name (for enums, records, errors, etc).
     pub fn class_name(nm: &str) -> String {
         let name = Self::sanitize_identifier(&nm.to_upper_camel_case());
@@ -99,6 +125,51 @@ impl DartCodeOracle {
         }
     }
 
+    /// Render a default value for a value of type `ty`. The flag tells whether the expression
+    /// is constant, which Dart requires for default parameter values.
+    ///
+    /// Fails for a `Default` the bindings can't reproduce: which variant is an enum's default,
+    /// or what a custom type or object defaults to, is only known to the Rust code.
+    pub fn render_default_value(
+        default: &DefaultValueMetadata,
+        ty: &Type,
+        ci: &ComponentInterface,
+    ) -> Result<(String, bool)> {
+        Ok(match (default, ty) {
+            (DefaultValueMetadata::Literal(LiteralMetadata::EmptySequence), Type::Bytes) => {
+                ("Uint8List(0)".to_string(), false)
+            }
+            (DefaultValueMetadata::Literal(lit), _) => (Self::render_literal(lit), true),
+            // `#[uniffi(default)]` without a value means the type's `Default` implementation
+            (DefaultValueMetadata::Default, ty) => match ty {
+                Type::Boolean => ("false".to_string(), true),
+                Type::UInt8
+                | Type::Int8
+                | Type::UInt16
+                | Type::Int16
+                | Type::UInt32
+                | Type::Int32
+                | Type::UInt64
+                | Type::Int64 => ("0".to_string(), true),
+                Type::Float32 | Type::Float64 => ("0.0".to_string(), true),
+                Type::String => ("''".to_string(), true),
+                Type::Duration => ("Duration.zero".to_string(), true),
+                Type::Optional { .. } => ("null".to_string(), true),
+                Type::Sequence { .. } => ("[]".to_string(), true),
+                Type::Map { .. } => ("{}".to_string(), true),
+                Type::Bytes => ("Uint8List(0)".to_string(), false),
+                // Only a record whose fields all have defaults can be built without arguments
+                Type::Record { name, .. } => match ci.get_record_definition(name) {
+                    Some(record) if record.fields().iter().all(|f| f.default_value().is_some()) => {
+                        (format!("{}()", Self::class_name(name)), false)
+                    }
+                    _ => bail!("record `{name}` has fields without a default value"),
+                },
+                _ => bail!("the `Default` of {ty:?} has no Dart equivalent"),
+            },
+        })
+    }
+
     /// Get the idiomatic Dart rendering of an FFI callback function name
     fn ffi_callback_name(nm: &str) -> String {
         format!(
@@ -274,6 +345,7 @@ impl DartCodeOracle {
             Type::Float32 | Type::Float64 => inner,
             Type::Boolean
             | Type::Duration
+            | Type::Timestamp
             | Type::String
             | Type::Object { .. }
             | Type::Enum { .. }
@@ -291,6 +363,12 @@ impl DartCodeOracle {
         quote!($ffi_func)
     }
 
+    /// With @Native, async functions are called directly by name
+    pub fn async_cancel(callable: impl Callable, ci: &ComponentInterface) -> dart::Tokens {
+        let ffi_func = callable.ffi_rust_future_cancel(ci);
+        quote!($ffi_func)
+    }
+
     /// With @Native, async functions are called directly by name
     pub fn async_complete(callable: impl Callable, ci: &ComponentInterface) -> dart::Tokens {
         let ffi_func = callable.ffi_rust_future_complete(ci);
@@ -303,6 +381,11 @@ impl DartCodeOracle {
         quote!($ffi_func)
     }
 
+    /// Optional named parameter appended to every async function and method.
+    pub fn cancellation_token_param() -> dart::Tokens {
+        quote!(UniffiCancellationToken? cancellationToken)
+    }
+
     /// Get the idiomatic Dart rendering of a class name based on `Type`.
     pub fn dart_type_label(type_: Option<&Type>) -> dart::Tokens {
         if let Some(ret_type) = type_ {
@@ -382,7 +465,7 @@ impl DartCodeOracle {
                 Type::Boolean => quote!(Int8),
                 Type::Bytes => quote!(RustBuffer),
                 Type::String => quote!(RustBuffer),
-                Type::Timestamp => quote!(Int64),
+                Type::Timestamp => quote!(RustBuffer),
                 Type::Duration => quote!(Int64),
                 Type::Optional { inner_type } => match **inner_type {
                     Type::String => quote!(RustBuffer),
@@ -395,10 +478,8 @@ impl DartCodeOracle {
                 Type::Record { module_path, .. } => {
                     Self::rust_buffer_name_with_path(module_path, ci)
                 }
-                Type::Custom { name, .. } => {
-                    let class_name = &DartCodeOracle::class_name(name);
-                    quote!($class_name)
-                }
+                // Custom types cross the FFI as their builtin type
+                Type::Custom { builtin, .. } => Self::native_type_label(Some(builtin), ci),
                 _ => quote!(Pointer<Void>),
             }
         } else {
@@ -426,7 +507,7 @@ impl DartCodeOracle {
                 Type::Boolean => quote!(int),
                 Type::Bytes => quote!(RustBuffer),
                 Type::String => quote!(RustBuffer),
-                Type::Timestamp => quote!(int),
+                Type::Timestamp => quote!(RustBuffer),
                 Type::Duration => quote!(int),
                 Type::Optional { inner_type } => match **inner_type {
                     Type::String => quote!(RustBuffer),
@@ -439,10 +520,7 @@ impl DartCodeOracle {
                 Type::Record { module_path, .. } => {
                     Self::rust_buffer_name_with_path(module_path, ci)
                 }
-                Type::Custom { name, .. } => {
-                    let type_name = &DartCodeOracle::class_name(name);
-                    quote!($type_name)
-                }
+                Type::Custom { builtin, .. } => Self::native_dart_type_label(Some(builtin), ci),
                 _ => quote!(dynamic),
             }
         } else {
@@ -474,33 +552,13 @@ impl DartCodeOracle {
                     outReturn.value = result ? 1 : 0;
                 )
             }
-            Type::Optional { inner_type } => {
-                // For optional return values
-                if let Type::String = **inner_type {
-                    quote!(
-                        final result = obj.$method_name($(for arg in &args => $arg,));
-                        if (result == null) {
-                            outReturn.ref = toRustBuffer(Uint8List.fromList([0]));
-                        } else {
-                            final lowered = FfiConverterOptionalString.lower(result);
-                            outReturn.ref = toRustBuffer(lowered.asUint8List());
-                        }
-                    )
-                } else {
-                    let lowered = ret_type.as_codetype().ffi_converter_name();
-                    quote!(
-                        final result = obj.$method_name($(for arg in &args => $arg,));
-                        if (result == null) {
-                            outReturn.ref = toRustBuffer(Uint8List.fromList([0]));
-                        } else {
-                            final lowered = $lowered.lower(result);
-                            final buffer = Uint8List(1 + lowered.len);
-                            buffer[0] = 1;
-                            buffer.setAll(1, lowered.asUint8List());
-                            outReturn.ref = toRustBuffer(buffer);
-                        }
-                    )
-                }
+            Type::Optional { .. } => {
+                // For optional return values, the converter writes the tag and the value
+                let lowered = ret_type.as_codetype().ffi_converter_name();
+                quote!(
+                    final result = obj.$method_name($(for arg in &args => $arg,));
+                    outReturn.ref = $lowered.lower(result);
+                )
             }
             Type::String => {
                 // For string return values
@@ -510,7 +568,18 @@ impl DartCodeOracle {
                     status.code = CALL_SUCCESS;
                 )
             }
-            Type::Object { .. } => {
+            Type::Object { .. }
+            | Type::UInt8
+            | Type::UInt16
+            | Type::UInt32
+            | Type::UInt64
+            | Type::Int8
+            | Type::Int16
+            | Type::Int32
+            | Type::Int64
+            | Type::Float32
+            | Type::Float64 => {
+                // Primitives and handles are written straight into the out pointer
                 let lowered = ret_type.as_codetype().ffi_converter_name();
                 quote!(
                     final result = obj.$method_name($(for arg in &args => $arg,));
@@ -544,12 +613,67 @@ impl DartCodeOracle {
         }
     }
 
+    /// Catches the Dart exception mapped to a callback method's error type and reports it as
+    /// `CALL_ERROR`, so Rust receives `Err(..)` rather than an unexpected callback error.
+    /// Trailing `rustCall` argument naming the Rust callable, recorded on the errors and panics
+    /// it raises.
+    pub fn function_name_arg(rust_name: &str) -> dart::Tokens {
+        quote!(, $(format!("\"{rust_name}\"")))
+    }
+
+    pub fn callback_error_catch(throws_type: Option<&Type>, status: dart::Tokens) -> dart::Tokens {
+        let Some(error_type) = throws_type else {
+            return quote!();
+        };
+        let exception = error_type.as_codetype().type_label();
+        let converter = error_type.as_codetype().ffi_converter_name();
+        let lowered = match error_type {
+            // Interface errors are written to the buffer as their handle
+            Type::Object { .. } => quote! {
+                toRustBuffer(Uint8List(8)..buffer.asByteData().setInt64(0, $converter.lower(e).address))
+            },
+            _ => quote!($converter.lower(e)),
+        };
+        quote! {
+            on $exception catch (e) {
+                $(&status).code = CALL_ERROR;
+                $(&status).errorBuf = $lowered;
+            }
+        }
+    }
+
+    /// Suffix of the foreign-future result struct and completion callback for a return type,
+    /// e.g. `U32` for `UniffiForeignFutureResultU32`.
+    pub fn foreign_future_suffix(ret_type: Option<&Type>) -> String {
+        let ffi_type = ret_type.map(FfiType::from);
+        FfiType::return_type_name(ffi_type.as_ref()).to_upper_camel_case()
+    }
+
+    /// Lowers the value an async callback method resolved to into its result struct field.
+    pub fn foreign_future_return_value(ret_type: &Type, value: dart::Tokens) -> dart::Tokens {
+        let converter = ret_type.as_codetype().ffi_converter_name();
+        match FfiType::from(ret_type) {
+            FfiType::Handle => quote!($converter.lower($value).address),
+            _ => quote!($converter.lower($value)),
+        }
+    }
+
     // Method to get the appropriate return type for callback functions
     pub fn callback_out_return_type(ret_type: Option<&Type>) -> dart::Tokens {
         if let Some(ret) = ret_type {
             match ret {
                 Type::Boolean => quote!(Pointer<Int8>),
                 Type::Object { .. } => quote!(Pointer<Pointer<Void>>),
+                Type::UInt8 => quote!(Pointer<Uint8>),
+                Type::UInt16 => quote!(Pointer<Uint16>),
+                Type::UInt32 => quote!(Pointer<Uint32>),
+                Type::UInt64 => quote!(Pointer<Uint64>),
+                Type::Int8 => quote!(Pointer<Int8>),
+                Type::Int16 => quote!(Pointer<Int16>),
+                Type::Int32 => quote!(Pointer<Int32>),
+                Type::Int64 => quote!(Pointer<Int64>),
+                Type::Float32 => quote!(Pointer<Float>),
+                Type::Float64 => quote!(Pointer<Double>),
                 _ => quote!(Pointer<RustBuffer>),
             }
         } else {
@@ -733,6 +857,7 @@ impl<T: AsType> AsCodeType for T {
             Type::Float64 => Box::new(primitives::Float64CodeType),
             Type::Boolean => Box::new(primitives::BooleanCodeType),
             Type::String => Box::new(primitives::StringCodeType),
+            Type::Timestamp => Box::new(primitives::TimestampCodeType),
             Type::Duration => Box::new(primitives::DurationCodeType),
             Type::Bytes => Box::new(primitives::BytesCodeType),
             Type::Object { name, imp, .. } => Box::new(objects::ObjectCodeType::new(name, imp)),
@@ -763,7 +888,44 @@ impl<T: AsType> AsCodeType for T {
                 module_path,
                 builtin,
             } => Box::new(custom::CustomCodeType::new(name, module_path, builtin)),
-            _ => todo!("As Type for Type::{:?}", self.as_type()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docstring_renders_dartdoc_lines() {
+        let mut tokens = DartCodeOracle::docstring(Some(
+            "Adds `a` and `b`.\n\nSee [Counter] and {@macro x}. */",
+        ));
+        tokens.append(quote!(int add(int a, int b);));
+        assert_eq!(
+            tokens.to_file_string().unwrap(),
+            "/// Adds `a` and `b`.\n///\n/// See [Counter] and {\\@macro x}. */\nint add(int a, int b);\n"
+        );
+    }
+
+    #[test]
+    fn docstring_is_indented_with_the_item() {
+        let mut tokens = quote!(class Counter $("{"));
+        tokens.indent();
+        tokens.append(DartCodeOracle::docstring(Some(
+            "First line\n  second line  ",
+        )));
+        tokens.append(quote!(int count();));
+        tokens.unindent();
+        tokens.append("}");
+        assert_eq!(
+            tokens.to_file_string().unwrap(),
+            "class Counter {\n    /// First line\n    ///   second line\n    int count();\n}\n"
+        );
+    }
+
+    #[test]
+    fn missing_docstring_renders_nothing() {
+        assert!(DartCodeOracle::docstring(None).is_empty());
+    }
+}
@@ -35,12 +35,13 @@ macro_rules! impl_renderable_for_primitive {
                 quote! {
                     class $cl_name {
                         static $type_signature lift(RustBuffer value) {
-                            return $cl_name.read(value.asUint8List()).value;
+                            return liftFromRustBuffer(value, $cl_name.read);
                         }
 
                         static LiftRetVal<$type_signature> read(Uint8List buf) {
                             final length = buf.buffer.asByteData(buf.offsetInBytes).getInt32(0);
-                            final bytes = Uint8List.view(buf.buffer, buf.offsetInBytes + 4, length);
+                            // Copied, the view would point into a buffer that is about to be freed
+                            final bytes = Uint8List.fromList(Uint8List.view(buf.buffer, buf.offsetInBytes + 4, length));
                             return LiftRetVal(bytes, length + 4);
                         }
 
@@ -3,6 +3,7 @@ mod macros;
 mod boolean;
 mod duration;
 mod string;
+mod timestamp;
 
 use crate::gen::render::{Renderable, TypeHelperRenderer};
 use crate::gen::CodeType;
@@ -13,6 +14,7 @@ use uniffi_bindgen::pipeline::general::nodes::{Literal, Radix, Type, TypeNode};
 pub use boolean::BooleanCodeType;
 pub use duration::DurationCodeType;
 pub use string::StringCodeType;
+pub use timestamp::TimestampCodeType;
 
 fn render_literal(literal: &Literal) -> String {
     fn typed_number(type_node: &TypeNode, num_str: String) -> String {
@@ -19,8 +19,12 @@ impl Renderable for StringCodeType {
         quote! {
             class FfiConverterString {
                 static String lift( RustBuffer buf) {
-                    // reading the entire buffer, the len is where the string finishes
-                    return utf8.decoder.convert(buf.asUint8List());
+                    try {
+                        // reading the entire buffer, the len is where the string finishes
+                        return utf8.decoder.convert(buf.asUint8List());
+                    } finally {
+                        buf.free();
+                    }
                 }
 
                 static RustBuffer lower( String value) {
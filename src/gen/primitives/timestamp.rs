@@ -0,0 +1,54 @@
+use crate::gen::{
+    quote,
+    render::{Renderable, TypeHelperRenderer},
+};
+
+use super::paste;
+use genco::lang::dart;
+
+impl_code_type_for_primitive!(TimestampCodeType, "DateTime", "Timestamp");
+
+impl Renderable for TimestampCodeType {
+    fn render_type_helper(&self, _type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
+        quote! {
+            class FfiConverterTimestamp {
+                static DateTime lift( RustBuffer buf) {
+                    return liftFromRustBuffer(buf, FfiConverterTimestamp.read);
+                }
+
+                static RustBuffer lower( DateTime value) {
+                    final buf = Uint8List(allocationSize(value));
+                    write(value, buf);
+                    return toRustBuffer(buf);
+                }
+
+                // Timestamps are serialized as signed seconds since the Unix epoch followed by
+                // unsigned nanoseconds. Pre-epoch values carry the sign on the seconds only.
+                static LiftRetVal<DateTime> read( Uint8List buf) {
+                    final bytes = buf.buffer.asByteData(buf.offsetInBytes, 12);
+                    final seconds = bytes.getInt64(0);
+                    final nanos = bytes.getUint32(8);
+                    final micros = seconds.abs() * 1000000 + (nanos ~/ 1000);
+                    return LiftRetVal(
+                        DateTime.fromMicrosecondsSinceEpoch(seconds < 0 ? -micros : micros, isUtc: true),
+                        12,
+                    );
+                }
+
+                static int allocationSize([DateTime? value]) {
+                    return 12;
+                }
+
+                static int write( DateTime value, Uint8List buf) {
+                    final bytes = buf.buffer.asByteData(buf.offsetInBytes, 12);
+                    final micros = value.microsecondsSinceEpoch;
+                    final sign = micros < 0 ? -1 : 1;
+                    final absMicros = micros.abs();
+                    bytes.setInt64(0, sign * (absMicros ~/ 1000000));
+                    bytes.setUint32(8, (absMicros % 1000000) * 1000);
+                    return 12;
+                }
+            }
+        }
+    }
+}
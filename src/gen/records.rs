@@ -1,10 +1,13 @@
+use super::objects::generate_uniffi_trait_methods;
 use super::oracle::{AsCodeType, DartCodeOracle};
 use super::render::{Renderable, TypeHelperRenderer};
 use super::types::generate_type;
 use crate::gen::CodeType;
+use anyhow::{Context, Result};
 use genco::prelude::*;
-use uniffi_bindgen::interface::{AsType, Record};
+use uniffi_bindgen::interface::{AsType, Field, Record, Type};
 use uniffi_bindgen::pipeline::general::nodes::Literal;
+use uniffi_bindgen::ComponentInterface;
 
 #[derive(Debug)]
 pub struct RecordCodeType {
@@ -46,22 +49,182 @@ impl Renderable for RecordCodeType {
     }
 }
 
+/// Fails naming the first record field whose default value can't be rendered in Dart.
+pub fn check_default_values(ci: &ComponentInterface) -> Result<()> {
+    for record in ci.record_definitions() {
+        for field in record.fields() {
+            if let Some(default) = field.default_value() {
+                DartCodeOracle::render_default_value(default, &field.as_type(), ci).with_context(
+                    || {
+                        format!(
+                            "Cannot render the default value of `{}.{}`",
+                            record.name(),
+                            field.name()
+                        )
+                    },
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn generate_record(obj: &Record, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
     let cls_name = &DartCodeOracle::class_name(obj.name());
     let ffi_conv_name = &DartCodeOracle::class_name(&obj.as_codetype().ffi_converter_name());
     for f in obj.fields() {
         type_helper.include_once_check(&f.as_codetype().canonical_name(), &f.as_type());
     }
+    let fields = obj.fields();
+
+    // Records with defaults take named parameters, so callers can leave those fields out.
+    // Defaults Dart does not accept as constants are filled in by the initializer list.
+    let named = fields.iter().any(|f| f.default_value().is_some());
+    let mut ctor_params = Vec::new();
+    let mut ctor_initializers = Vec::new();
+    for f in fields {
+        let name = DartCodeOracle::var_name(f.name());
+        let ty = f.as_type();
+        match f.default_value() {
+            None if named => ctor_params.push(quote!(required this.$name)),
+            None => ctor_params.push(quote!(this.$name)),
+            Some(default) => {
+                match DartCodeOracle::render_default_value(default, &ty, type_helper.get_ci())
+                    .expect("default values are checked by `check_default_values`")
+                {
+                    // Named parameters are null unless given, no need to spell that out
+                    (value, true) if value == "null" => ctor_params.push(quote!(this.$name)),
+                    (value, true) => ctor_params.push(quote!(this.$name = $value)),
+                    (value, false) => {
+                        ctor_params.push(quote!($(generate_type(&ty))? $(&name)));
+                        ctor_initializers.push(quote!($(&name) = $(&name) ?? $value));
+                    }
+                }
+            }
+        }
+    }
+    let ctor_params = if named {
+        quote!({$(for p in ctor_params => $p, )})
+    } else {
+        quote!($(for p in ctor_params => $p, ))
+    };
+    let ctor_initializers = if ctor_initializers.is_empty() {
+        quote!()
+    } else {
+        quote!( : $(for i in ctor_initializers join (, ) => $i))
+    };
+    let ctor_arg = |f: &Field, value: dart::Tokens| {
+        if named {
+            quote!($(DartCodeOracle::var_name(f.name())): $value)
+        } else {
+            value
+        }
+    };
+
+    let field_equality = fields.iter().map(|f| {
+        let name = DartCodeOracle::var_name(f.name());
+        if DartCodeOracle::needs_deep_equality(&f.as_type()) {
+            quote!(uniffiDeepEquals(this.$(&name), other.$(&name)))
+        } else {
+            quote!(this.$(&name) == other.$(&name))
+        }
+    });
+    let field_hashes = fields.iter().map(|f| {
+        let name = DartCodeOracle::var_name(f.name());
+        if DartCodeOracle::needs_deep_equality(&f.as_type()) {
+            quote!(uniffiDeepHash($name))
+        } else {
+            quote!($name)
+        }
+    });
+    let field_descriptions = fields
+        .iter()
+        .map(|f| {
+            let name = DartCodeOracle::var_name(f.name());
+            format!("{name}: ${name}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let to_string = format!("\"{cls_name}({field_descriptions})\"");
+
+    // Nullable parameters default to a sentinel, otherwise `copyWith(field: null)` could not
+    // tell "clear this field" apart from "keep it".
+    let copy_with_params = fields.iter().map(|f| {
+        let name = DartCodeOracle::var_name(f.name());
+        match f.as_type() {
+            Type::Optional { .. } => quote!(Object? $name = uniffiCopyWithUnset),
+            ty => quote!($(generate_type(&ty))? $name),
+        }
+    });
+    let copy_with_args = fields.iter().map(|f| {
+        let name = DartCodeOracle::var_name(f.name());
+        let value = match f.as_type() {
+            Type::Optional { .. } => quote!(identical($(&name), uniffiCopyWithUnset) ? this.$(&name) : $(&name) as $(generate_type(&f.as_type()))),
+            _ => quote!($(&name) ?? this.$(&name)),
+        };
+        ctor_arg(f, value)
+    });
+    // Exported uniffi traits replace the structural implementations below
+    let trait_methods = obj.uniffi_trait_methods();
+    let uniffi_trait_methods = generate_uniffi_trait_methods(
+        cls_name,
+        &trait_methods,
+        &quote!($ffi_conv_name.lower(this)),
+        type_helper,
+    );
+    let implements_clause = if trait_methods.ord_cmp.is_some() {
+        quote!( implements Comparable<$cls_name>)
+    } else {
+        quote!()
+    };
+
+    let read_args = fields
+        .iter()
+        .map(|f| ctor_arg(f, quote!($(DartCodeOracle::var_name(f.name())))))
+        .collect::<Vec<_>>();
+
     quote! {
-        class $cls_name {
-            $(for f in obj.fields() => final $(generate_type(&f.as_type())) $(DartCodeOracle::var_name(f.name()));)
+        $(DartCodeOracle::docstring(obj.docstring()))
+        class $cls_name $implements_clause {
+            $(for f in fields {
+                $(DartCodeOracle::docstring(f.docstring()))
+                final $(generate_type(&f.as_type())) $(DartCodeOracle::var_name(f.name()));
+            })
+
+            $(cls_name)($ctor_params)$ctor_initializers;
+
+            $cls_name copyWith($(if !fields.is_empty() { {$(for p in copy_with_params => $p, )} })) {
+                return $cls_name($(for a in copy_with_args => $a, ));
+            }
+
+            $(if trait_methods.eq_eq.is_none() {
+                @override
+                bool operator ==(Object other) {
+                    return identical(this, other) ||
+                        (other is $cls_name $(for eq in field_equality => && $eq));
+                }
+            })
+
+            $(if trait_methods.hash_hash.is_none() {
+                @override
+                int get hashCode => Object.hashAll([$(for h in field_hashes => $h, )]);
+            })
+
+            $(if trait_methods.display_fmt.is_none() {
+                @override
+                String toString() {
+                    return $(&to_string);
+                }
+            })
+
+            $uniffi_trait_methods
 
-            $(cls_name)($(for f in obj.fields() => this.$(DartCodeOracle::var_name(f.name())), ));
+            $(type_helper.get_config().value_type_methods().generate_members(&obj.as_type(), type_helper))
         }
 
         class $ffi_conv_name {
             static $cls_name lift( RustBuffer buf) {
-                return $ffi_conv_name.read(buf.asUint8List()).value;
+                return liftFromRustBuffer(buf, $ffi_conv_name.read);
             }
 
             static LiftRetVal<$cls_name> read( Uint8List buf) {
@@ -73,7 +236,7 @@ pub fn generate_record(obj: &Record, type_helper: &dyn TypeHelperRenderer) -> da
                     new_offset += $(DartCodeOracle::var_name(f.name()))_lifted.bytesRead;
                 )
                 return LiftRetVal($(cls_name)(
-                    $(for f in obj.fields() => $(DartCodeOracle::var_name(f.name())),)
+                    $(for arg in read_args => $arg,)
                 ), new_offset - buf.offsetInBytes);
             }
 
@@ -1,5 +1,5 @@
 use super::{callback_interface, compounds, custom, enums, primitives, records};
-use super::{objects, oracle::AsCodeType};
+use super::{objects, oracle::AsCodeType, Config};
 use genco::{lang::dart, quote};
 use uniffi_bindgen::interface::{AsType, Enum, Object, Record, Type};
 use uniffi_bindgen::ComponentInterface;
@@ -10,6 +10,7 @@ pub trait Renderer<T> {
 
 pub trait TypeHelperRenderer {
     fn get_ci(&self) -> &ComponentInterface;
+    fn get_config(&self) -> &Config;
     fn include_once_check(&self, name: &str, ty: &Type) -> bool;
     fn check(&self, name: &str) -> bool;
 
@@ -49,9 +50,9 @@ pub trait Renderable {
             Type::Enum { name, .. } => quote!($(DartCodeOracle::class_name(name))),
             Type::Record { name, .. } => quote!($name),
             Type::Custom { name, .. } => quote!($name),
+            Type::Timestamp => quote!(DateTime),
             Type::Duration => quote!(Duration),
             Type::CallbackInterface { name, .. } => quote!($name),
-            _ => todo!("Type::{:?}", ty),
         };
 
         if !type_helper.include_once_check(&ty.as_codetype().canonical_name(), ty) {
@@ -83,6 +84,7 @@ impl<T: AsType> AsRenderable for T {
             Type::Float64 => Box::new(primitives::Float64CodeType),
             Type::Boolean => Box::new(primitives::BooleanCodeType),
             Type::String => Box::new(primitives::StringCodeType),
+            Type::Timestamp => Box::new(primitives::TimestampCodeType),
             Type::Duration => Box::new(primitives::DurationCodeType),
             Type::Bytes => Box::new(primitives::BytesCodeType),
             Type::Object { name, imp, .. } => Box::new(objects::ObjectCodeType::new(name, imp)),
@@ -112,7 +114,6 @@ impl<T: AsType> AsRenderable for T {
             Type::CallbackInterface { name, .. } => Box::new(
                 callback_interface::CallbackInterfaceCodeType::new(name, self.as_type()),
             ),
-            _ => todo!("Renderable for Type::{:?}", self.as_type()),
         }
     }
 }
@@ -0,0 +1,107 @@
+use anyhow::{bail, Result};
+use genco::prelude::*;
+use goblin::mach::Mach;
+use goblin::Object;
+use uniffi_bindgen::ComponentInterface;
+
+use super::{CallbackDispatch, Config};
+
+/// Whether the bindings call into `uniffi_dart_runtime`, which frees objects on finalization
+/// and carries thread-safe callbacks.
+pub fn uses_runtime(ci: &ComponentInterface, config: &Config) -> bool {
+    !ci.object_definitions().is_empty() || tracks_isolate(ci, config)
+}
+
+/// Whether Rust calls have to record that the isolate is blocked in them, which thread-safe
+/// callbacks need to tell a call on the isolate's own thread from one on another thread.
+pub fn tracks_isolate(ci: &ComponentInterface, config: &Config) -> bool {
+    let thread_safe = |name: &str| config.callback_dispatch(name) == CallbackDispatch::ThreadSafe;
+    ci.callback_interface_definitions()
+        .iter()
+        .any(|callback| thread_safe(callback.name()))
+        || ci
+            .object_definitions()
+            .iter()
+            .any(|obj| obj.has_callback_interface() && thread_safe(obj.name()))
+}
+
+/// Checks that the library in `data` exports the runtime the bindings of `ci` call into, so a
+/// missing `uniffi_dart_runtime::setup!()` fails here rather than when Dart loads the library.
+/// Formats other than shared libraries are not checked.
+pub fn check_runtime_linked(ci: &ComponentInterface, config: &Config, data: &[u8]) -> Result<()> {
+    if !uses_runtime(ci, config) {
+        return Ok(());
+    }
+    const PROBE: &str = "uniffi_dart_finalize";
+    let exported = match Object::parse(data)? {
+        Object::Elf(elf) => elf
+            .dynsyms
+            .iter()
+            .any(|sym| sym.st_shndx != 0 && elf.dynstrtab.get_at(sym.st_name) == Some(PROBE)),
+        // Darwin prepends a `_` to symbol names
+        Object::Mach(Mach::Binary(macho)) => macho
+            .exports()?
+            .iter()
+            .any(|export| export.name.strip_prefix('_') == Some(PROBE)),
+        Object::PE(pe) => pe.exports.iter().any(|export| export.name == Some(PROBE)),
+        _ => return Ok(()),
+    };
+    if !exported {
+        bail!(
+            "the bindings for `{}` need the uniffi-dart runtime for objects or thread-safe \
+             callbacks, but the library does not export it. Add `uniffi_dart_runtime` to the \
+             dependencies of the crate and invoke `uniffi_dart_runtime::setup!()` in it.",
+            ci.crate_name()
+        );
+    }
+    Ok(())
+}
+
+/// Declarations of the `uniffi_dart_runtime` functions, plus the finalizer releasing Rust
+/// objects and the id this isolate registers callback implementations under.
+pub fn generate_runtime_definitions(asset_id: &str) -> dart::Tokens {
+    quote! {
+        @Native<Pointer<Void> Function(Pointer<Void>, Pointer<Void>)>(assetId: $asset_id, isLeaf: true)
+        external Pointer<Void> uniffi_dart_finalizer_token_new(Pointer<Void> free, Pointer<Void> handle);
+
+        @Native<Void Function(Pointer<Void>)>(assetId: $asset_id, isLeaf: true)
+        external void uniffi_dart_finalizer_token_free(Pointer<Void> token);
+
+        @Native<Void Function(Pointer<Void>)>(assetId: $asset_id)
+        external void uniffi_dart_finalize(Pointer<Void> token);
+
+        @Native<Uint64 Function()>(assetId: $asset_id, isLeaf: true)
+        external int uniffi_dart_isolate_id_new();
+
+        @Native<Uint64 Function(Uint64)>(assetId: $asset_id, isLeaf: true)
+        external int uniffi_dart_isolate_enter(int isolate);
+
+        @Native<Void Function(Uint64)>(assetId: $asset_id, isLeaf: true)
+        external void uniffi_dart_isolate_exit(int previous);
+
+        @Native<Bool Function(Uint64)>(assetId: $asset_id, isLeaf: true)
+        external bool uniffi_dart_isolate_entered(int isolate);
+
+        @Native<Uint64 Function(Uint64, Uint64)>(assetId: $asset_id, isLeaf: true)
+        external int uniffi_dart_callback_owner_claim(int interface, int isolate);
+
+        @Native<Uint64 Function(Uint64)>(assetId: $asset_id, isLeaf: true)
+        external int uniffi_dart_rendezvous_new(int isolate);
+
+        @Native<Bool Function(Uint64)>(assetId: $asset_id, isLeaf: true)
+        external bool uniffi_dart_rendezvous_claim(int rendezvous);
+
+        @Native<Void Function(Uint64, Uint64)>(assetId: $asset_id, isLeaf: true)
+        external void uniffi_dart_rendezvous_signal(int rendezvous, int result);
+
+        @Native<Uint64 Function(Uint64, Pointer<RustCallStatus>)>(assetId: $asset_id)
+        external int uniffi_dart_rendezvous_wait(int rendezvous, Pointer<RustCallStatus> callStatus);
+
+        // Frees Rust objects whose Dart wrapper was garbage collected without being disposed.
+        // It also runs when the isolate shuts down, which a Dart `Finalizer` would not.
+        final _uniffiObjectFinalizer = NativeFinalizer(
+            Native.addressOf<NativeFunction<Void Function(Pointer<Void>)>>(uniffi_dart_finalize));
+
+        final int _uniffiIsolateId = uniffi_dart_isolate_id_new();
+    }
+}
@@ -3,23 +3,27 @@ use std::{cell::RefCell, collections::HashMap};
 
 use genco::prelude::*;
 use uniffi_bindgen::interface::AsType;
+use uniffi_bindgen::interface::{Argument, FfiType};
 use uniffi_bindgen::{interface::Type, ComponentInterface};
 
 use super::render::{AsRenderable, Renderable, Renderer, TypeHelperRenderer};
 use super::{enums, functions, objects, oracle::AsCodeType, records};
 use crate::gen::oracle::DartCodeOracle;
+use crate::gen::Config;
 
 type FunctionDefinition = dart::Tokens;
 
 pub struct TypeHelpersRenderer<'a> {
     ci: &'a ComponentInterface,
+    config: &'a Config,
     include_once_names: RefCell<HashMap<String, Type>>,
 }
 
 impl<'a> TypeHelpersRenderer<'a> {
-    pub fn new(ci: &'a ComponentInterface) -> Self {
+    pub fn new(ci: &'a ComponentInterface, config: &'a Config) -> Self {
         Self {
             ci,
+            config,
             include_once_names: RefCell::new(HashMap::new()),
         }
     }
@@ -57,6 +61,10 @@ impl TypeHelperRenderer for TypeHelpersRenderer<'_> {
         self.ci
     }
 
+    fn get_config(&self) -> &Config {
+        self.config
+    }
+
     fn get_record(&self, name: &str) -> Option<&uniffi_bindgen::interface::Record> {
         self.ci.get_record_definition(name)
     }
@@ -86,11 +94,24 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
             })
             .collect::<BTreeSet<_>>();
         // The second import statement uses a library prefix, to distinguish conflicting identifiers e.g. RustBuffer vs. ext.RustBuffer
+        // Custom types may bring their own Dart libraries, e.g. `package:uuid/uuid.dart`
+        let custom_type_imports = self
+            .ci
+            .iter_local_types()
+            .filter_map(|ty| match ty {
+                Type::Custom { name, .. } => self.config.custom_type(name),
+                _ => None,
+            })
+            .flat_map(|custom| custom.imports().iter().cloned())
+            .collect::<BTreeSet<_>>();
         let imports: dart::Tokens = quote!(
             $( for imp in modules_to_import {
                 $(format!("import \"{}.dart\"", imp));
                 $(format!("import \"{}.dart\"", imp)) as $imp;
             })
+            $( for imp in custom_type_imports {
+                $(format!("import \"{}\"", imp));
+            })
         );
 
         // let function_definitions = quote!($( for fun in self.ci.function_definitions() => $(functions::generate_function("this", fun, self))));
@@ -115,6 +136,31 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
             callback_code.append(callback_codetype.render_type_helper(self));
         }
 
+        // Async callback methods complete through UniFFI's foreign-future structs
+        let foreign_future_definitions = if self.ci.has_async_callback_interface_definition() {
+            super::callback_interface::generate_foreign_future_definitions()
+        } else {
+            quote!()
+        };
+
+        // Thread-safe callbacks need to know when the isolate is blocked in a Rust call, see
+        // `uniffi_dart_runtime`
+        let (enter_rust, exit_rust) = if super::runtime::tracks_isolate(self.ci, self.config) {
+            (
+                quote!(final previousIsolate = uniffi_dart_isolate_enter(_uniffiIsolateId);),
+                quote!(uniffi_dart_isolate_exit(previousIsolate);),
+            )
+        } else {
+            (quote!(), quote!())
+        };
+
+        let tracking = self.config.debug_tracking();
+        let debug_tracking_definitions = if tracking {
+            generate_debug_tracking()
+        } else {
+            quote!()
+        };
+
         // Let's include the string converter
         self.include_once_check(&Type::String.as_codetype().canonical_name(), &Type::String);
         let helpers_definitions = quote! {
@@ -181,9 +227,50 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                 }
             }
 
+            // Base of every error type generated from Rust, so one `on UniffiException` clause
+            // handles them all.
+            abstract class UniffiException implements Exception {
+                UniffiException({this.rustFunctionName});
+
+                // Name of the Rust type the error was lifted from.
+                String get rustTypeName;
+
+                // The message Rust gave the error, null if it has none.
+                String? get rustMessage => null;
+
+                // The Rust function or method whose call raised the error, e.g. `Store::get`.
+                // Null for errors created on the Dart side.
+                final String? rustFunctionName;
+            }
+
+            // Thrown when Rust panics during a call, as opposed to `UniffiInternalError` which
+            // reports problems in the bindings themselves.
+            class RustPanicException implements Exception {
+                // The panic message.
+                final String message;
+
+                // The Rust function or method that panicked, e.g. `Store::get`.
+                final String? functionName;
+
+                const RustPanicException(this.message, [this.functionName]);
+
+                @override
+                String toString() {
+                    if (functionName == null) {
+                        return $[str](RustPanicException: $message);
+                    }
+                    return $[str](RustPanicException in $functionName: $message);
+                }
+            }
+
+            // Called with every Rust panic before it is thrown, e.g. to report it to crash
+            // analytics.
+            void Function(RustPanicException panic)? onRustPanic;
+
             const int CALL_SUCCESS = 0;
             const int CALL_ERROR = 1;
             const int CALL_UNEXPECTED_ERROR = 2;
+            const int CALL_CANCELLED = 3;
 
             final class RustCallStatus extends Struct {
                 @Int8()
@@ -194,56 +281,71 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                 //Pointer<RustCallStatus> asPointer() => Pointer<RustCallStatus>.fromAddress(address);
             }
 
-            void checkCallStatus(UniffiRustCallStatusErrorHandler errorHandler, Pointer<RustCallStatus> status) {
+            void checkCallStatus(UniffiRustCallStatusErrorHandler errorHandler, Pointer<RustCallStatus> status, [String? functionName]) {
 
                 if (status.ref.code == CALL_SUCCESS) {
                 return;
                 } else if (status.ref.code == CALL_ERROR) {
-                throw errorHandler.lift(status.ref.errorBuf);
+                $(if tracking { _uniffiOutstandingRustBuffers++; })
+                throw errorHandler.lift(status.ref.errorBuf, functionName);
                 } else if (status.ref.code == CALL_UNEXPECTED_ERROR) {
-                if (status.ref.errorBuf.len > 0) {
-                    throw UniffiInternalError.panicked(FfiConverterString.lift(status.ref.errorBuf));
-                } else {
-                    throw UniffiInternalError.panicked("Rust panic");
-                }
+                $(if tracking {
+                    if (status.ref.errorBuf.len > 0) {
+                        _uniffiOutstandingRustBuffers++;
+                    }
+                })
+                final panic = RustPanicException(
+                    status.ref.errorBuf.len > 0 ? FfiConverterString.lift(status.ref.errorBuf) : "Rust panic",
+                    functionName,
+                );
+                onRustPanic?.call(panic);
+                throw panic;
+                } else if (status.ref.code == CALL_CANCELLED) {
+                throw const UniffiCancelledException();
                 } else {
                 throw UniffiInternalError.panicked("Unexpected RustCallStatus code: ${status.ref.code}");
                 }
             }
 
-            T rustCall<T>(T Function(Pointer<RustCallStatus>) callback, [UniffiRustCallStatusErrorHandler? errorHandler]) {
+            T rustCall<T>(T Function(Pointer<RustCallStatus>) callback, [UniffiRustCallStatusErrorHandler? errorHandler, String? functionName]) {
                 final status = calloc<RustCallStatus>();
+                $(&enter_rust)
                 try {
                     final result = callback(status);
-                    checkCallStatus(errorHandler ?? NullRustCallStatusErrorHandler(), status);
+                    checkCallStatus(errorHandler ?? NullRustCallStatusErrorHandler(), status, functionName);
+                    $(if tracking { _uniffiTrackReceivedRustBuffer(result); })
                     return result;
                 } finally {
+                $(&exit_rust)
                 calloc.free(status);
                 }
             }
 
             // New version that separates FFI call from lifting to avoid deserializing garbage on error
-            T rustCallWithLifter<T, F>(F Function(Pointer<RustCallStatus>) ffiCall, T Function(F) lifter, [UniffiRustCallStatusErrorHandler? errorHandler]) {
+            T rustCallWithLifter<T, F>(F Function(Pointer<RustCallStatus>) ffiCall, T Function(F) lifter, [UniffiRustCallStatusErrorHandler? errorHandler, String? functionName]) {
                 final status = calloc<RustCallStatus>();
+                $(&enter_rust)
                 try {
                     final rawResult = ffiCall(status);
-                    checkCallStatus(errorHandler ?? NullRustCallStatusErrorHandler(), status);
+                    checkCallStatus(errorHandler ?? NullRustCallStatusErrorHandler(), status, functionName);
+                    $(if tracking { _uniffiTrackReceivedRustBuffer(rawResult); })
                     return lifter(rawResult);
                 } finally {
+                    $(&exit_rust)
                     calloc.free(status);
                 }
             }
 
             class NullRustCallStatusErrorHandler extends UniffiRustCallStatusErrorHandler {
                 @override
-                Exception lift(RustBuffer errorBuf) {
+                Exception lift(RustBuffer errorBuf, [String? functionName]) {
                 errorBuf.free();
                 return UniffiInternalError.panicked("Unexpected CALL_ERROR");
                 }
             }
 
             abstract class UniffiRustCallStatusErrorHandler {
-                Exception lift(RustBuffer errorBuf);
+                Exception lift(RustBuffer errorBuf, [String? functionName]);
             }
 
             final class RustBuffer extends Struct {
@@ -269,10 +371,12 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                 // }
 
                 void free() {
+                    $(if tracking { _uniffiOutstandingRustBuffers--; })
                     rustCall((status) => $(self.ci.ffi_rustbuffer_free().name())(this, status));
                 }
 
                 RustBuffer reserve(int additionalCapacity) {
+                $(if tracking { _uniffiOutstandingRustBuffers--; })
                 return rustCall((status) => $(self.ci.ffi_rustbuffer_reserve().name())(this, additionalCapacity, status));
                 }
 
@@ -288,17 +392,35 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                 }
             }
 
+            // Copies `data` into a new Rust-owned buffer. Ownership passes to whoever the buffer is
+            // handed to, normally Rust itself as a call argument.
             RustBuffer toRustBuffer(Uint8List data) {
                 final length = data.length;
 
                 final Pointer<Uint8> frameData = calloc<Uint8>(length); // Allocate a pointer large enough.
-                final pointerList = frameData.asTypedList(length); // Create a list that uses our pointer and copy in the data.
-                pointerList.setAll(0, data); // FIXME: can we remove this memcopy somehow?
-
                 final bytes = calloc<ForeignBytes>();
-                bytes.ref.len = length;
-                bytes.ref.data = frameData;
-                return RustBuffer.fromBytes(bytes.ref);
+                try {
+                    final pointerList = frameData.asTypedList(length); // Create a list that uses our pointer and copy in the data.
+                    pointerList.setAll(0, data); // FIXME: can we remove this memcopy somehow?
+
+                    bytes.ref.len = length;
+                    bytes.ref.data = frameData;
+                    // Rust copies the bytes, the staging memory is ours to release
+                    return RustBuffer.fromBytes(bytes.ref);
+                } finally {
+                    calloc.free(frameData);
+                    calloc.free(bytes);
+                }
+            }
+
+            // Reads a value out of a buffer received from Rust and frees the buffer. A `RustBuffer`
+            // belongs to whoever receives it, so every `lift(RustBuffer)` goes through here.
+            T liftFromRustBuffer<T>(RustBuffer buf, LiftRetVal<T> Function(Uint8List) read) {
+                try {
+                    return read(buf.asUint8List()).value;
+                } finally {
+                    buf.free();
+                }
             }
 
             final class ForeignBytes extends Struct {
@@ -330,6 +452,52 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                 }
             }
 
+            // Structural equality for generated value types. Collections are compared element by
+            // element, everything else falls back to `==`.
+            bool uniffiDeepEquals(Object? a, Object? b) {
+                if (identical(a, b)) {
+                    return true;
+                }
+                if (a is List && b is List) {
+                    if (a.length != b.length) {
+                        return false;
+                    }
+                    for (var i = 0; i < a.length; i++) {
+                        if (!uniffiDeepEquals(a[i], b[i])) {
+                            return false;
+                        }
+                    }
+                    return true;
+                }
+                if (a is Map && b is Map) {
+                    if (a.length != b.length) {
+                        return false;
+                    }
+                    for (final key in a.keys) {
+                        if (!b.containsKey(key) || !uniffiDeepEquals(a[key], b[key])) {
+                            return false;
+                        }
+                    }
+                    return true;
+                }
+                return a == b;
+            }
+
+            // A hash consistent with `uniffiDeepEquals`.
+            int uniffiDeepHash(Object? value) {
+                if (value is List) {
+                    return Object.hashAll(value.map(uniffiDeepHash));
+                }
+                if (value is Map) {
+                    return Object.hashAllUnordered(value.entries
+                        .map((e) => Object.hash(uniffiDeepHash(e.key), uniffiDeepHash(e.value))));
+                }
+                return value.hashCode;
+            }
+
+            // Default for nullable `copyWith` parameters, so passing `null` can clear a field.
+            const Object uniffiCopyWithUnset = Object();
+
             abstract class FfiConverter<D, F> {
                 const FfiConverter();
 
@@ -373,25 +541,73 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
 
             typedef UniffiRustFutureContinuationCallback = Void Function(Uint64, Int8);
 
+            class UniffiCancelledException implements Exception {
+                final String message;
+
+                const UniffiCancelledException([this.message = "The Rust future was cancelled"]);
+
+                @override
+                String toString() => $[str](UniffiCancelledException: $message);
+            }
+
+            // Passed to async functions and methods to cancel the underlying Rust future.
+            // Cancelling drops the future on the Rust side and completes the Dart future
+            // with an `UniffiCancelledException`.
+            class UniffiCancellationToken {
+                bool _isCancelled = false;
+                final List<void Function()> _listeners = [];
+
+                bool get isCancelled => _isCancelled;
+
+                void cancel() {
+                    if (_isCancelled) {
+                        return;
+                    }
+                    _isCancelled = true;
+                    final listeners = List.of(_listeners);
+                    _listeners.clear();
+                    for (final listener in listeners) {
+                        listener();
+                    }
+                }
+
+                void _addListener(void Function() listener) => _listeners.add(listener);
+
+                void _removeListener(void Function() listener) => _listeners.remove(listener);
+            }
+
             Future<T> uniffiRustCallAsync<T, F>(
                 Pointer<Void> Function() rustFutureFunc,
                 void Function(Pointer<Void>, Pointer<NativeFunction<UniffiRustFutureContinuationCallback>>, Pointer<Void>) pollFunc,
+                void Function(Pointer<Void>) cancelFunc,
                 F Function(Pointer<Void>, Pointer<RustCallStatus>) completeFunc,
                 void Function(Pointer<Void>) freeFunc,
                 T Function(F) liftFunc, [
                 UniffiRustCallStatusErrorHandler? errorHandler,
+                UniffiCancellationToken? cancellationToken,
+                String? functionName,
             ]) async {
+                if (cancellationToken?.isCancelled ?? false) {
+                    throw const UniffiCancelledException();
+                }
+
                 final rustFuture = rustFutureFunc();
                 final completer = Completer<int>();
 
                 late final NativeCallable<UniffiRustFutureContinuationCallback> callback;
 
                 void poll() {
-                    pollFunc(
-                        rustFuture,
-                        callback.nativeFunction,
-                        Pointer<Void>.fromAddress(0),
-                    );
+                    // Polling runs the future on this thread
+                    $(&enter_rust)
+                    try {
+                        pollFunc(
+                            rustFuture,
+                            callback.nativeFunction,
+                            Pointer<Void>.fromAddress(0),
+                        );
+                    } finally {
+                        $(&exit_rust)
+                    }
                 }
                 void onResponse(int _idx, int pollResult) {
                     if (pollResult == UNIFFI_RUST_FUTURE_POLL_READY) {
@@ -400,34 +616,52 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                         poll();
                     }
                 }
+                // Cancelling wakes the pending continuation with a ready result, the
+                // complete call below then reports CALL_CANCELLED.
+                void onCancel() => cancelFunc(rustFuture);
+
                 callback = NativeCallable<UniffiRustFutureContinuationCallback>.listener(onResponse);
+                cancellationToken?._addListener(onCancel);
 
                 try {
                     poll();
                     await completer.future;
                     callback.close();
+                    cancellationToken?._removeListener(onCancel);
 
 
                     final status = calloc<RustCallStatus>();
                     try {
-
                         final result = completeFunc(rustFuture, status);
-
+                        // Check the status before lifting, the result is garbage on error
+                        checkCallStatus(errorHandler ?? NullRustCallStatusErrorHandler(), status, functionName);
+                        $(if tracking { _uniffiTrackReceivedRustBuffer(result); })
                         return liftFunc(result);
                     } finally {
                         calloc.free(status);
                     }
                 } finally {
+                    cancellationToken?._removeListener(onCancel);
                     freeFunc(rustFuture);
                 }
             }
 
+            $(foreign_future_definitions)
+
             // As of uniffi 0.30, foreign handles must always have the lowest bit set
             // This is achieved here with an odd number sequence.
             class UniffiHandleMap<T> {
+                // The interface whose implementations are stored, used in debug stats
+                final String name;
                 final Map<int, T> _map = {};
                 int _counter = 1;
 
+                UniffiHandleMap(this.name) {
+                $(if tracking { _uniffiHandleMaps.add(this); })
+                }
+
+                int get length => _map.length;
+
                 int insert(T obj) {
                 final handle = _counter;
                 _counter += 2;
@@ -452,12 +686,117 @@ impl Renderer<(FunctionDefinition, dart::Tokens)> for TypeHelpersRenderer<'_> {
                 }
             }
 
+            $(debug_tracking_definitions)
         };
 
         (types_helper_code, function_definitions)
     }
 }
 
+/// `hook` when the bindings are generated with `debug_tracking`, nothing otherwise.
+pub(super) fn debug_tracking_hook(
+    type_helper: &dyn TypeHelperRenderer,
+    hook: dart::Tokens,
+) -> dart::Tokens {
+    if type_helper.get_config().debug_tracking() {
+        hook
+    } else {
+        quote!()
+    }
+}
+
+/// `lowered`, passed to Rust as an argument of type `ty`. With `debug_tracking`, a RustBuffer
+/// passed this way is counted as handed over to Rust.
+pub(super) fn hand_over_arg(
+    ty: &Type,
+    lowered: dart::Tokens,
+    type_helper: &dyn TypeHelperRenderer,
+) -> dart::Tokens {
+    if type_helper.get_config().debug_tracking()
+        && matches!(FfiType::from(ty), FfiType::RustBuffer(_))
+    {
+        quote!(_uniffiHandOverRustBuffer($lowered))
+    } else {
+        lowered
+    }
+}
+
+/// The lowered `arg` of a call to Rust, see [`hand_over_arg`].
+pub(super) fn lower_arg(arg: &Argument, type_helper: &dyn TypeHelperRenderer) -> dart::Tokens {
+    hand_over_arg(
+        &arg.as_type(),
+        DartCodeOracle::lower_arg_with_callback_handling(arg),
+        type_helper,
+    )
+}
+
+/// Counters behind `uniffiDebugStats()`, only generated with `debug_tracking`.
+fn generate_debug_tracking() -> dart::Tokens {
+    quote! {
+        final Map<String, int> _uniffiLiveObjects = {};
+        // RustBuffers Dart owns: received from Rust, or allocated for Rust and not handed over
+        // yet. Freeing a buffer or passing it to Rust gives it up.
+        int _uniffiOutstandingRustBuffers = 0;
+        final List<UniffiHandleMap> _uniffiHandleMaps = [];
+
+        // Objects are freed by native finalizers that run no Dart code, so their collection is
+        // observed through a Dart finalizer of its own.
+        final _uniffiObjectTracker = Finalizer<String>((className) => _uniffiTrackObject(className, -1));
+
+        // Called with the result of a successful Rust call, which Dart owns if it is a buffer
+        void _uniffiTrackReceivedRustBuffer(Object? result) {
+            if (result is RustBuffer) {
+                _uniffiOutstandingRustBuffers++;
+            }
+        }
+
+        // Wraps a buffer lowered as an argument of a Rust call, which takes it over
+        RustBuffer _uniffiHandOverRustBuffer(RustBuffer buffer) {
+            _uniffiOutstandingRustBuffers--;
+            return buffer;
+        }
+
+        void _uniffiTrackObject(String className, int delta) {
+            final count = (_uniffiLiveObjects[className] ?? 0) + delta;
+            if (count == 0) {
+                _uniffiLiveObjects.remove(className);
+            } else {
+                _uniffiLiveObjects[className] = count;
+            }
+        }
+
+        // Snapshot of the native resources currently held through the bindings.
+        class UniffiDebugStats {
+            // Objects per class that have been neither disposed nor finalized.
+            final Map<String, int> liveObjects;
+
+            // RustBuffers owned by Dart that have been neither freed nor handed to Rust.
+            final int outstandingRustBuffers;
+
+            // Dart implementations per callback interface that Rust still holds a handle to.
+            final Map<String, int> handleMapEntries;
+
+            const UniffiDebugStats(this.liveObjects, this.outstandingRustBuffers, this.handleMapEntries);
+
+            @override
+            String toString() {
+                return $[str](UniffiDebugStats(liveObjects: $liveObjects, outstandingRustBuffers: $outstandingRustBuffers, handleMapEntries: $handleMapEntries));
+            }
+        }
+
+        UniffiDebugStats uniffiDebugStats() {
+            return UniffiDebugStats(
+                Map.unmodifiable(_uniffiLiveObjects),
+                _uniffiOutstandingRustBuffers,
+                Map.unmodifiable({
+                    for (final map in _uniffiHandleMaps)
+                        if (map.length > 0) map.name: map.length,
+                }),
+            );
+        }
+    }
+}
+
 pub fn generate_type(ty: &Type) -> dart::Tokens {
     match ty {
         Type::UInt8
@@ -480,6 +819,7 @@ pub fn generate_type(ty: &Type) -> dart::Tokens {
             value_type,
         } => quote!(Map<$(generate_type(key_type)), $(generate_type(value_type))>),
         Type::Enum { name, .. } => quote!($(DartCodeOracle::class_name(name))),
+        Type::Timestamp => quote!(DateTime),
         Type::Duration => quote!(Duration),
         Type::Record { name, .. } => quote!($name),
         Type::Custom { name, .. } => quote!($name),
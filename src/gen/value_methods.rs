@@ -0,0 +1,485 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use genco::prelude::*;
+use goblin::mach::symbols::{NO_SECT, N_SECT};
+use goblin::mach::Mach;
+use goblin::Object;
+use uniffi::metadata::codes;
+use uniffi_bindgen::interface::ffi::FfiFunction;
+use uniffi_bindgen::interface::{Function, Method, Type};
+use uniffi_bindgen::macro_metadata;
+use uniffi_meta::{ConstructorMetadata, FnMetadata, Metadata, MetadataGroup};
+
+use super::objects::{generate_ffi_call, generate_method_with_receiver};
+use super::oracle::DartCodeOracle;
+use super::render::TypeHelperRenderer;
+use super::types::hand_over_arg;
+
+/// Methods and constructors exported with `#[uniffi::export] impl` on records and enums.
+///
+/// `ComponentInterface` only knows how to attach these to objects, so they are split off the
+/// metadata before the interface is built and rendered next to the type they belong to.
+#[derive(Debug, Default, Clone)]
+pub struct ValueTypeMethods {
+    methods: BTreeMap<String, Vec<Method>>,
+    constructors: BTreeMap<String, Vec<ValueTypeConstructor>>,
+}
+
+/// A constructor of a record or enum. It is called like a function returning the type, only
+/// the scaffolding and checksum functions are named after the constructor.
+#[derive(Debug, Clone)]
+struct ValueTypeConstructor {
+    function: Function,
+    ffi_func: FfiFunction,
+    checksum_fn_name: String,
+    checksum: u16,
+}
+
+impl ValueTypeConstructor {
+    fn new(meta: ConstructorMetadata, self_type: Type) -> Result<Self> {
+        let ffi_name = meta.ffi_symbol_name();
+        let checksum_fn_name = meta.checksum_symbol_name();
+        let checksum = meta
+            .checksum
+            .context("constructor metadata without a checksum")?;
+        let mut function = Function::from(FnMetadata {
+            module_path: meta.module_path,
+            name: meta.name,
+            is_async: meta.is_async,
+            inputs: meta.inputs,
+            return_type: Some(self_type),
+            throws: meta.throws,
+            checksum: meta.checksum,
+            docstring: meta.docstring,
+        });
+        function.derive_ffi_func()?;
+        let mut ffi_func = function.ffi_func().clone();
+        ffi_func.rename(ffi_name);
+        Ok(Self {
+            function,
+            ffi_func,
+            checksum_fn_name,
+            checksum,
+        })
+    }
+}
+
+impl ValueTypeMethods {
+    /// Remove the methods of records and enums from `group`, and take the constructors of its
+    /// records and enums out of `constructors`.
+    pub fn split_from(
+        group: &mut MetadataGroup,
+        constructors: &mut Vec<ConstructorMetadata>,
+    ) -> Result<Self> {
+        let value_types: HashMap<String, Type> = group
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Metadata::Record(meta) => Some((
+                    meta.name.clone(),
+                    Type::Record {
+                        module_path: meta.module_path.clone(),
+                        name: meta.name.clone(),
+                    },
+                )),
+                Metadata::Enum(meta) => Some((
+                    meta.name.clone(),
+                    Type::Enum {
+                        module_path: meta.module_path.clone(),
+                        name: meta.name.clone(),
+                    },
+                )),
+                _ => None,
+            })
+            .collect();
+
+        let mut split = Self::default();
+        for item in std::mem::take(&mut group.items) {
+            match item {
+                Metadata::Method(meta) if value_types.contains_key(&meta.self_name) => {
+                    let self_name = meta.self_name.clone();
+                    let mut method = Method::from_metadata(meta, value_types[&self_name].clone());
+                    method.derive_ffi_func()?;
+                    split.methods.entry(self_name).or_default().push(method);
+                }
+                item => {
+                    group.items.insert(item);
+                }
+            }
+        }
+
+        let crate_name = &group.namespace.crate_name;
+        for meta in std::mem::take(constructors) {
+            let in_group = meta.module_path.split("::").next() == Some(crate_name.as_str());
+            match value_types.get(&meta.self_name) {
+                Some(self_type) if in_group => {
+                    let self_name = meta.self_name.clone();
+                    let constructor = ValueTypeConstructor::new(meta, self_type.clone())?;
+                    split
+                        .constructors
+                        .entry(self_name)
+                        .or_default()
+                        .push(constructor);
+                }
+                _ => constructors.push(meta),
+            }
+        }
+        Ok(split)
+    }
+
+    pub fn iter_ffi_function_definitions(&self) -> impl Iterator<Item = &FfiFunction> {
+        self.methods
+            .values()
+            .flatten()
+            .map(Method::ffi_func)
+            .chain(self.constructors.values().flatten().map(|c| &c.ffi_func))
+    }
+
+    pub fn iter_checksums(&self) -> impl Iterator<Item = (&str, u16)> {
+        self.methods
+            .values()
+            .flatten()
+            .map(|m| (m.checksum_fn_name(), m.checksum()))
+            .chain(
+                self.constructors
+                    .values()
+                    .flatten()
+                    .map(|c| (c.checksum_fn_name.as_str(), c.checksum)),
+            )
+    }
+
+    /// Dart members for a record or enum, rendered inside its class body.
+    pub fn generate_members(
+        &self,
+        self_type: &Type,
+        type_helper: &dyn TypeHelperRenderer,
+    ) -> dart::Tokens {
+        let type_name = self_type.name().expect("records and enums are named");
+        let cls_name = &DartCodeOracle::class_name(type_name);
+        let methods = self.methods.get(type_name).into_iter().flatten();
+        let constructors = self.constructors.get(type_name).into_iter().flatten();
+        // Records and enums are passed by value, so `this` is lowered into a fresh buffer per call
+        let receiver = hand_over_arg(
+            self_type,
+            DartCodeOracle::type_lower_fn(self_type, quote!(this)),
+            type_helper,
+        );
+        // Factory constructors can't be async, async constructors become static methods
+        let generate_constructor = |c: &ValueTypeConstructor| {
+            let name = DartCodeOracle::fn_name(c.function.name());
+            generate_ffi_call(
+                &c.function,
+                c.ffi_func.name(),
+                quote!(),
+                |ret| {
+                    if c.function.is_async() {
+                        quote!(static $ret $(&name))
+                    } else {
+                        quote!(factory $cls_name.$(&name))
+                    }
+                },
+                &format!("{type_name}::{}", c.function.name()),
+                type_helper,
+            )
+        };
+        quote! {
+            $(for c in constructors => $(generate_constructor(c)))
+            $(for m in methods => $(generate_method_with_receiver(m, receiver.clone(), type_helper)))
+        }
+    }
+}
+
+/// Reads the metadata of the library in `data`, for `BindgenLoader::load_metadata_specialized`.
+///
+/// The UniFFI 0.30 metadata reader rejects constructors that don't return an object, which is
+/// every constructor of a record or enum. Those are collected into `constructors` instead.
+pub fn read_library_metadata(
+    data: &[u8],
+    constructors: &mut Vec<ConstructorMetadata>,
+) -> Result<Option<Vec<Metadata>>> {
+    // Libraries without such constructors are read by UniFFI as usual
+    let error = match macro_metadata::extract_from_bytes(data) {
+        Ok(items) => return Ok(Some(items)),
+        Err(error) => error,
+    };
+    let Some(symbols) = metadata_symbols(data)? else {
+        return Err(error);
+    };
+
+    let mut seen = HashSet::new();
+    let mut items = vec![];
+    for (name, offset) in symbols {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let buf = data
+            .get(offset..)
+            .with_context(|| format!("metadata for '{name}' is out of bounds"))?;
+        match Metadata::read(buf) {
+            Ok(item) => items.push(item),
+            Err(_) if buf.first() == Some(&codes::CONSTRUCTOR) => {
+                constructors
+                    .push(read_value_type_constructor(buf).with_context(|| {
+                        format!("extracting constructor metadata for '{name}'")
+                    })?);
+            }
+            Err(error) => {
+                return Err(error).with_context(|| format!("extracting metadata for '{name}'"))
+            }
+        }
+    }
+    Ok(Some(items))
+}
+
+/// Constructor metadata is laid out like method metadata, so it is read as a method returning
+/// `Self`. The checksum the reader computes covers the altered code byte, so it is computed
+/// again from the original bytes.
+fn read_value_type_constructor(buf: &[u8]) -> Result<ConstructorMetadata> {
+    let mut as_method = buf.to_vec();
+    as_method[0] = codes::METHOD;
+    let Metadata::Method(meta) = Metadata::read(&as_method)? else {
+        unreachable!("method metadata is read as a method");
+    };
+    Ok(ConstructorMetadata {
+        module_path: meta.module_path,
+        self_name: meta.self_name,
+        name: meta.name,
+        is_async: meta.is_async,
+        inputs: meta.inputs,
+        throws: meta.throws,
+        checksum: Some(uniffi::checksum_metadata(&buf[..metadata_len(&as_method)])),
+        docstring: meta.docstring,
+    })
+}
+
+/// The length of the metadata item at the start of `buf`, which the reader does not tell.
+///
+/// A prefix of `buf` padded with 0xFF bytes only reads if it holds the whole item. Otherwise the
+/// reader runs into a string of 0xFF bytes, which is not valid UTF-8. The padding holds the
+/// longest string, as the reader panics when it runs out of bytes in one.
+fn metadata_len(buf: &[u8]) -> usize {
+    const PADDING: usize = 1 << 17;
+    let reads = |len: usize| {
+        let mut prefix = buf[..len].to_vec();
+        prefix.resize(len + PADDING, 0xFF);
+        Metadata::read(&prefix).is_ok()
+    };
+    // Double the prefix until it holds the item, then bisect
+    let mut len = 1;
+    while len < buf.len() && !reads(len) {
+        len *= 2;
+    }
+    let (mut short, mut long) = (len / 2, len.min(buf.len()));
+    while long - short > 1 {
+        let mid = (short + long) / 2;
+        if reads(mid) {
+            long = mid;
+        } else {
+            short = mid;
+        }
+    }
+    long
+}
+
+/// Names and file offsets of the metadata symbols in a shared library, `None` for a format left
+/// to UniFFI.
+fn metadata_symbols(data: &[u8]) -> Result<Option<Vec<(String, usize)>>> {
+    // Darwin prepends a `_` to symbol names
+    let is_metadata = |name: &str| {
+        name.strip_prefix('_')
+            .unwrap_or(name)
+            .starts_with("UNIFFI_META")
+    };
+    let symbols = match Object::parse(data)? {
+        Object::Elf(elf) => {
+            let mut symbols = vec![];
+            for sym in elf.syms.iter() {
+                let Some(name) = elf.strtab.get_at(sym.st_name) else {
+                    continue;
+                };
+                if !is_metadata(name) {
+                    continue;
+                }
+                // Extended section indexes are not worth handling here
+                let Some(section) = elf.section_headers.get(sym.st_shndx) else {
+                    return Ok(None);
+                };
+                let offset = section.sh_offset + sym.st_value - section.sh_addr;
+                symbols.push((name.to_string(), offset as usize));
+            }
+            symbols
+        }
+        // Like UniFFI, look at both the symbol table and the exports
+        Object::Mach(Mach::Binary(macho)) => {
+            let mut sections = vec![];
+            for segment_sections in macho.segments.sections() {
+                for section in segment_sections {
+                    sections.push(section?.0);
+                }
+            }
+            sections.sort_by_key(|section| section.addr);
+            let mut symbols = vec![];
+            for symbol in macho.symbols() {
+                let (name, nlist) = symbol?;
+                if !nlist.is_global()
+                    || nlist.get_type() != N_SECT
+                    || nlist.n_sect == NO_SECT as usize
+                    || !is_metadata(name)
+                {
+                    continue;
+                }
+                let Some(section) = sections.get(nlist.n_sect - 1) else {
+                    return Ok(None);
+                };
+                let offset = section.offset as u64 + nlist.n_value - section.addr;
+                symbols.push((name.to_string(), offset as usize));
+            }
+            for export in macho.exports()? {
+                if is_metadata(&export.name) {
+                    symbols.push((export.name, export.offset as usize));
+                }
+            }
+            symbols
+        }
+        Object::PE(pe) => pe
+            .exports
+            .iter()
+            .filter_map(|export| Some((export.name?, export.offset?)))
+            .filter(|(name, _)| is_metadata(name))
+            .map(|(name, offset)| (name.to_string(), offset))
+            .collect(),
+        _ => return Ok(None),
+    };
+    Ok(Some(symbols))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniffi::MetadataBuffer;
+
+    // What `#[uniffi::export]` emits for `Profile::anonymous(name: String) -> Self`
+    fn constructor_metadata() -> MetadataBuffer {
+        MetadataBuffer::from_code(codes::CONSTRUCTOR)
+            .concat_str("profiles")
+            .concat_str("Profile")
+            .concat_str("anonymous")
+            .concat_bool(false)
+            .concat_value(1)
+            .concat_str("name")
+            .concat_value(codes::TYPE_STRING)
+            .concat_bool(false)
+            .concat_value(codes::TYPE_RECORD)
+            .concat_str("profiles")
+            .concat_str("Profile")
+            .concat_long_str("A profile nobody owns.")
+    }
+
+    // A 64-bit Mach-O file with one section, mapped at a different address than its file offset,
+    // and a symbol table listing `symbol` at `offset` into it.
+    fn macho_with_symbol(symbol: &str, contents: &[u8], offset: u64) -> Vec<u8> {
+        const HEADER: usize = 32;
+        const SEGMENT: usize = 72 + 80;
+        const SYMTAB: usize = 24;
+        let section_offset = HEADER + SEGMENT + SYMTAB;
+        let section_addr = 0x4000 + section_offset as u64;
+        let symbols_offset = section_offset + contents.len();
+        let strings_offset = symbols_offset + 16;
+        let strings = [b"\0", symbol.as_bytes(), b"\0"].concat();
+
+        let mut data = vec![];
+        let u32 = |data: &mut Vec<u8>, value: u32| data.extend(value.to_le_bytes());
+        let u64 = |data: &mut Vec<u8>, value: u64| data.extend(value.to_le_bytes());
+        let name = |data: &mut Vec<u8>, name: &str| {
+            data.extend(name.as_bytes());
+            data.resize(data.len() + 16 - name.len(), 0);
+        };
+        // mach_header_64 of an x86-64 dylib
+        for value in [
+            0xfeedfacf,
+            0x0100_0007,
+            3,
+            6,
+            2,
+            (SEGMENT + SYMTAB) as u32,
+            0,
+            0,
+        ] {
+            u32(&mut data, value);
+        }
+        // segment_command_64
+        u32(&mut data, 0x19);
+        u32(&mut data, SEGMENT as u32);
+        name(&mut data, "__DATA");
+        u64(&mut data, section_addr);
+        u64(&mut data, contents.len() as u64);
+        u64(&mut data, section_offset as u64);
+        u64(&mut data, contents.len() as u64);
+        for value in [3, 3, 1, 0] {
+            u32(&mut data, value);
+        }
+        // section_64
+        name(&mut data, "__const");
+        name(&mut data, "__DATA");
+        u64(&mut data, section_addr);
+        u64(&mut data, contents.len() as u64);
+        for value in [section_offset as u32, 0, 0, 0, 0, 0, 0, 0] {
+            u32(&mut data, value);
+        }
+        // symtab_command
+        for value in [0x2, SYMTAB as u32, symbols_offset as u32, 1] {
+            u32(&mut data, value);
+        }
+        u32(&mut data, strings_offset as u32);
+        u32(&mut data, strings.len() as u32);
+
+        data.extend(contents);
+        // nlist_64 of an external symbol defined in section 1
+        u32(&mut data, 1);
+        data.extend([0x0f, 1, 0, 0]);
+        u64(&mut data, section_addr + offset);
+        data.extend(strings);
+        data
+    }
+
+    #[test]
+    fn constructor_keeps_the_checksum_of_its_metadata() {
+        let metadata = constructor_metadata();
+        let mut contents = vec![0xAA; 8];
+        contents.extend(metadata.as_ref());
+        contents.extend([0xAA; 8]);
+        let data = macho_with_symbol(
+            "_UNIFFI_META_PROFILES_CONSTRUCTOR_PROFILE_ANONYMOUS",
+            &contents,
+            8,
+        );
+
+        let mut constructors = vec![];
+        let items = read_library_metadata(&data, &mut constructors)
+            .unwrap()
+            .unwrap();
+        assert!(items.is_empty());
+        let [constructor] = &constructors[..] else {
+            panic!("expected one constructor, got {constructors:?}");
+        };
+        assert_eq!(constructor.self_name, "Profile");
+        assert_eq!(constructor.name, "anonymous");
+        assert_eq!(constructor.inputs[0].name, "name");
+        assert_eq!(
+            constructor.docstring.as_deref(),
+            Some("A profile nobody owns.")
+        );
+        assert_eq!(constructor.checksum, Some(metadata.checksum()));
+    }
+
+    #[test]
+    fn metadata_len_stops_at_the_end_of_the_item() {
+        let metadata = constructor_metadata();
+        let mut as_method = metadata.as_ref().to_vec();
+        as_method[0] = codes::METHOD;
+        let len = as_method.len();
+        as_method.extend([0u8; 64]);
+        assert_eq!(metadata_len(&as_method), len);
+    }
+}
@@ -0,0 +1,299 @@
+//! Native support code the generated Dart bindings call into.
+//!
+//! Everything here is exported with an unmangled name so the bindings can bind to it through
+//! `@Native`. A crate whose bindings need the runtime depends on this crate and links it in with
+//! [`setup!`](crate::setup). Unlike `uniffi-dart`, which generates the bindings, this crate ends
+//! up in the library shipped with them, so it depends on nothing but `uniffi`.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use uniffi::{RustCallStatus, RustCallStatusCode};
+
+/// Links the uniffi-dart runtime into the calling crate's library.
+///
+/// Bindings for objects and thread-safe callback interfaces call into the runtime, so crates
+/// defining either have to invoke this once, next to `uniffi::setup_scaffolding!()` or
+/// `uniffi::include_scaffolding!()`. Generating their bindings fails if the library lacks it.
+#[macro_export]
+macro_rules! setup {
+    () => {
+        #[doc(hidden)]
+        pub use $crate as uniffi_dart_runtime;
+    };
+}
+
+/// The `free` function UniFFI generates for every object.
+pub type ObjectFree = unsafe extern "C" fn(handle: u64, call_status: &mut RustCallStatus);
+
+/// What a Dart `NativeFinalizer` needs to free an object. Its callback takes a single pointer,
+/// while object `free` functions also take a `RustCallStatus`.
+pub struct FinalizerToken {
+    free: ObjectFree,
+    handle: u64,
+}
+
+/// A token freeing `handle` with `free` once passed to [`uniffi_dart_finalize`].
+#[no_mangle]
+pub extern "C" fn uniffi_dart_finalizer_token_new(
+    free: ObjectFree,
+    handle: u64,
+) -> *mut FinalizerToken {
+    Box::into_raw(Box::new(FinalizerToken { free, handle }))
+}
+
+/// Releases `token` without freeing its object, for objects disposed of explicitly.
+///
+/// # Safety
+///
+/// `token` must come from [`uniffi_dart_finalizer_token_new`] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_dart_finalizer_token_free(token: *mut FinalizerToken) {
+    drop(Box::from_raw(token));
+}
+
+/// Frees the object behind `token` and releases the token. Nobody is left to report a panic in
+/// the object's `Drop` to, so it is dropped silently.
+///
+/// # Safety
+///
+/// `token` must come from [`uniffi_dart_finalizer_token_new`] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_dart_finalize(token: *mut FinalizerToken) {
+    let token = Box::from_raw(token);
+    let mut status = RustCallStatus::default();
+    (token.free)(token.handle, &mut status);
+    if !matches!(status.code, RustCallStatusCode::Success) {
+        ManuallyDrop::into_inner(status.error_buf).destroy();
+    }
+}
+
+static NEXT_ISOLATE_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    // The isolate currently blocked in a Rust call on this thread, 0 for none
+    static ENTERED_ISOLATE: Cell<u64> = const { Cell::new(0) };
+}
+
+/// What the threads calling into Dart share: the isolates blocked in Rust calls, the isolates
+/// owning thread-safe callback interfaces and the calls waiting for an isolate to answer.
+struct Dispatch {
+    // How many Rust calls each isolate is blocked in, nested ones included
+    blocked: BTreeMap<u64, usize>,
+    owners: BTreeMap<u64, u64>,
+    rendezvous: BTreeMap<u64, Rendezvous>,
+    next_rendezvous: u64,
+}
+
+/// A call posted to an isolate, as seen by the thread waiting for its answer.
+#[derive(Clone, Copy)]
+enum Rendezvous {
+    /// Queued for `isolate`, which has not picked it up yet
+    Posted {
+        isolate: u64,
+    },
+    /// The isolate is running the call
+    Claimed,
+    Answered(u64),
+    /// The waiting thread gave up, so the call's arguments are gone
+    Abandoned,
+}
+
+static DISPATCH: Mutex<Dispatch> = Mutex::new(Dispatch {
+    blocked: BTreeMap::new(),
+    owners: BTreeMap::new(),
+    rendezvous: BTreeMap::new(),
+    next_rendezvous: 0,
+});
+
+// Notified whenever an isolate enters a Rust call or a rendezvous is signalled
+static CHANGED: Condvar = Condvar::new();
+
+fn dispatch() -> MutexGuard<'static, Dispatch> {
+    DISPATCH.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A new id for the calling isolate, never 0.
+#[no_mangle]
+pub extern "C" fn uniffi_dart_isolate_id_new() -> u64 {
+    NEXT_ISOLATE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Records that `isolate` is blocked in a Rust call on this thread. Returns the isolate recorded
+/// before, to be handed back to [`uniffi_dart_isolate_exit`] once the call returns.
+#[no_mangle]
+pub extern "C" fn uniffi_dart_isolate_enter(isolate: u64) -> u64 {
+    *dispatch().blocked.entry(isolate).or_default() += 1;
+    // Threads waiting for the isolate to answer give up, see `uniffi_dart_rendezvous_wait`
+    CHANGED.notify_all();
+    ENTERED_ISOLATE.with(|entered| entered.replace(isolate))
+}
+
+/// Undoes the matching [`uniffi_dart_isolate_enter`].
+#[no_mangle]
+pub extern "C" fn uniffi_dart_isolate_exit(previous: u64) {
+    let isolate = ENTERED_ISOLATE.with(|entered| entered.replace(previous));
+    let mut dispatch = dispatch();
+    if let Some(count) = dispatch.blocked.get_mut(&isolate) {
+        *count -= 1;
+        if *count == 0 {
+            dispatch.blocked.remove(&isolate);
+        }
+    }
+}
+
+/// Whether `isolate` is blocked in a Rust call on this thread, in which case a callback for it
+/// has to run right here rather than wait for the isolate to pick it up.
+#[no_mangle]
+pub extern "C" fn uniffi_dart_isolate_entered(isolate: u64) -> bool {
+    ENTERED_ISOLATE.with(|entered| entered.get() == isolate)
+}
+
+/// Makes `isolate` the owner of the callback interface identified by `interface`, unless another
+/// isolate owns it already, and returns the owner. Rust keeps a single vtable per interface, so
+/// a second isolate registering implementations would take over the calls meant for the first.
+#[no_mangle]
+pub extern "C" fn uniffi_dart_callback_owner_claim(interface: u64, isolate: u64) -> u64 {
+    *dispatch().owners.entry(interface).or_insert(isolate)
+}
+
+/// A rendezvous for a call posted to `isolate`. The isolate claims it with
+/// [`uniffi_dart_rendezvous_claim`] before running the call and signals it with
+/// [`uniffi_dart_rendezvous_signal`] afterwards, while the posting thread waits on it once with
+/// [`uniffi_dart_rendezvous_wait`].
+#[no_mangle]
+pub extern "C" fn uniffi_dart_rendezvous_new(isolate: u64) -> u64 {
+    let mut dispatch = dispatch();
+    dispatch.next_rendezvous += 1;
+    let rendezvous = dispatch.next_rendezvous;
+    dispatch
+        .rendezvous
+        .insert(rendezvous, Rendezvous::Posted { isolate });
+    rendezvous
+}
+
+/// Whether the isolate may run the call posted with `rendezvous`, which it may not once the
+/// waiting thread gave up on it.
+#[no_mangle]
+pub extern "C" fn uniffi_dart_rendezvous_claim(rendezvous: u64) -> bool {
+    let mut dispatch = dispatch();
+    match dispatch.rendezvous[&rendezvous] {
+        Rendezvous::Posted { .. } => {
+            dispatch.rendezvous.insert(rendezvous, Rendezvous::Claimed);
+            true
+        }
+        _ => {
+            dispatch.rendezvous.remove(&rendezvous);
+            false
+        }
+    }
+}
+
+/// Hands `result` to the thread waiting on the claimed `rendezvous`.
+#[no_mangle]
+pub extern "C" fn uniffi_dart_rendezvous_signal(rendezvous: u64, result: u64) {
+    dispatch()
+        .rendezvous
+        .insert(rendezvous, Rendezvous::Answered(result));
+    CHANGED.notify_all();
+}
+
+/// Blocks until `rendezvous` is signalled and returns the result it was signalled with.
+///
+/// An isolate blocked in a Rust call cannot pick up posted calls. Should that Rust call wait
+/// for this thread, directly or not, the wait would never end. So it is given up as soon as the
+/// isolate is blocked before claiming the call, returning 0 and, unless `call_status` is null,
+/// an unexpected error.
+///
+/// # Safety
+///
+/// `call_status` must be null or point to a `RustCallStatus` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn uniffi_dart_rendezvous_wait(
+    rendezvous: u64,
+    call_status: *mut RustCallStatus,
+) -> u64 {
+    let mut dispatch = dispatch();
+    loop {
+        match dispatch.rendezvous[&rendezvous] {
+            Rendezvous::Answered(result) => {
+                dispatch.rendezvous.remove(&rendezvous);
+                return result;
+            }
+            Rendezvous::Posted { isolate } if dispatch.blocked.contains_key(&isolate) => {
+                dispatch
+                    .rendezvous
+                    .insert(rendezvous, Rendezvous::Abandoned);
+                break;
+            }
+            _ => {
+                dispatch = CHANGED
+                    .wait(dispatch)
+                    .unwrap_or_else(PoisonError::into_inner)
+            }
+        }
+    }
+    drop(dispatch);
+    if let Some(call_status) = call_status.as_mut() {
+        *call_status = RustCallStatus::error(
+            "the isolate implementing the callback is blocked in a Rust call and cannot answer \
+             calls from other threads",
+        );
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn rendezvous_hands_over_the_answer() {
+        let isolate = uniffi_dart_isolate_id_new();
+        let rendezvous = uniffi_dart_rendezvous_new(isolate);
+        let answer = thread::spawn(move || {
+            assert!(uniffi_dart_rendezvous_claim(rendezvous));
+            uniffi_dart_rendezvous_signal(rendezvous, 42);
+        });
+        let mut status = RustCallStatus::default();
+        assert_eq!(
+            unsafe { uniffi_dart_rendezvous_wait(rendezvous, &mut status) },
+            42
+        );
+        assert!(matches!(status.code, RustCallStatusCode::Success));
+        answer.join().unwrap();
+    }
+
+    #[test]
+    fn rendezvous_is_abandoned_while_the_isolate_is_blocked() {
+        let isolate = uniffi_dart_isolate_id_new();
+        let previous = uniffi_dart_isolate_enter(isolate);
+        // Another thread calls back into the isolate, which is blocked in this thread's call
+        let waiter = thread::spawn(move || {
+            let rendezvous = uniffi_dart_rendezvous_new(isolate);
+            let mut status = RustCallStatus::default();
+            let result = unsafe { uniffi_dart_rendezvous_wait(rendezvous, &mut status) };
+            (rendezvous, result, status.code)
+        });
+        let (rendezvous, result, code) = waiter.join().unwrap();
+        uniffi_dart_isolate_exit(previous);
+
+        assert_eq!(result, 0);
+        assert!(matches!(code, RustCallStatusCode::UnexpectedError));
+        // Once the isolate gets to the call, it must leave it alone
+        assert!(!uniffi_dart_rendezvous_claim(rendezvous));
+    }
+
+    #[test]
+    fn callback_interfaces_keep_their_first_owner() {
+        let first = uniffi_dart_isolate_id_new();
+        let second = uniffi_dart_isolate_id_new();
+        assert_eq!(uniffi_dart_callback_owner_claim(1, first), first);
+        assert_eq!(uniffi_dart_callback_owner_claim(1, second), first);
+        assert_eq!(uniffi_dart_callback_owner_claim(1, first), first);
+    }
+}